@@ -5,6 +5,7 @@ extern crate proc_macro;
 mod read;
 mod types;
 mod utils;
+mod value;
 mod write;
 
 use proc_macro::TokenStream;
@@ -23,13 +24,55 @@ pub fn derive_xml_read(input: TokenStream) -> TokenStream {
 
     let where_clause = &generics.where_clause;
 
-    let input_lifetime = utils::gen_input_lifetime(&generics);
+    let input_lifetime = utils::gen_input_lifetime(generics);
 
     let mut params_with_input_lifetime = generics.params.clone();
 
     params_with_input_lifetime.insert(0, input_lifetime.into());
 
-    let impl_read = read::impl_read(Element::parse(input.clone()));
+    let element = Element::parse(input.clone());
+
+    if element.is_flatten() {
+        let impl_read = read::impl_read_flatten(element);
+
+        let gen = quote! {
+            impl <#params_with_input_lifetime> strong_xml::XmlReadFlatten<'__input> for #name <#params>
+                #where_clause
+            {
+                #impl_read
+            }
+        };
+
+        return gen.into();
+    }
+
+    let impl_read_text = read::impl_read_text(&element).map(|read| {
+        quote! {
+            impl <#params_with_input_lifetime> strong_xml::XmlReadText<'__input> for #name <#params>
+                #where_clause
+            {
+                fn from_text(
+                    __value: std::borrow::Cow<'__input, str>
+                ) -> strong_xml::XmlResult<Self> {
+                    use strong_xml::XmlError;
+                    #read
+                }
+            }
+        }
+    });
+
+    let impl_extend = read::impl_extend(&element).map(|extend| {
+        quote! {
+            fn extend_from_reader(
+                &mut self,
+                reader: &mut strong_xml::XmlReader<'__input>
+            ) -> strong_xml::XmlResult<()> {
+                #extend
+            }
+        }
+    });
+
+    let impl_read = read::impl_read(element);
 
     let gen = quote! {
         impl <#params_with_input_lifetime> strong_xml::XmlRead<'__input> for #name <#params>
@@ -42,7 +85,11 @@ pub fn derive_xml_read(input: TokenStream) -> TokenStream {
                 use strong_xml::XmlError;
                 #impl_read
             }
+
+            #impl_extend
         }
+
+        #impl_read_text
     };
 
     gen.into()
@@ -59,7 +106,23 @@ pub fn derive_xml_write(input: TokenStream) -> TokenStream {
 
     let where_clause = &generics.where_clause;
 
-    let impl_write = write::impl_write(Element::parse(input.clone()));
+    let element = Element::parse(input.clone());
+
+    if element.is_flatten() {
+        let impl_write = write::impl_write_flatten(element);
+
+        let gen = quote! {
+            impl <#params> strong_xml::XmlWriteFlatten for #name <#params>
+                #where_clause
+            {
+                #impl_write
+            }
+        };
+
+        return gen.into();
+    }
+
+    let impl_write = write::impl_write(element);
 
     let gen = quote! {
         impl <#params> strong_xml::XmlWrite for #name <#params>
@@ -78,3 +141,10 @@ pub fn derive_xml_write(input: TokenStream) -> TokenStream {
 
     gen.into()
 }
+
+#[proc_macro_derive(XmlValue, attributes(xml))]
+pub fn derive_xml_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    value::impl_value(input).into()
+}
@@ -1,5 +1,6 @@
 mod named;
 mod newtype;
+mod text;
 
 use crate::types::{Element, Fields};
 
@@ -12,22 +13,42 @@ pub fn impl_read(element: Element) -> TokenStream {
             name: ele_name,
             variants,
         } => {
+            // text variants are only read as mixed content
+            let variants = variants
+                .iter()
+                .filter(|variant| !matches!(variant, Fields::Text { .. }))
+                .collect::<Vec<_>>();
+
             let tags = variants.iter().map(|variant| match variant {
                 Fields::Newtype { tags, .. } => tags.clone(),
                 Fields::Named { tag, .. } => vec![tag.clone()],
+                Fields::Text { .. } | Fields::Flatten { .. } => unreachable!(),
             });
 
             let read = variants.iter().map(|variant| match variant {
-                Fields::Named { tag, name, fields } => {
-                    named::read(&tag, quote!(#ele_name::#name), &fields)
+                Fields::Named {
+                    tag,
+                    name,
+                    fields,
+                    deny_unknown,
+                    ordered,
+                } => named::read(
+                    tag,
+                    quote!(#ele_name::#name),
+                    fields,
+                    *deny_unknown,
+                    *ordered,
+                ),
+                Fields::Newtype { name, tags, ty } => {
+                    newtype::read(tags, ty, quote!(#ele_name::#name))
                 }
-                Fields::Newtype { name, ty, .. } => newtype::read(&ty, quote!(#ele_name::#name)),
+                Fields::Text { .. } | Fields::Flatten { .. } => unreachable!(),
             });
 
             quote! {
                 while let Some(tag) = reader.find_element_start(None)? {
                     match tag {
-                        #( #( #tags )|* => { #read } )*
+                        #( tag if #( reader.is_element(tag, #tags) )||* => { #read } )*
                         tag => {
                             strong_xml::log_skip_element!(#ele_name, tag);
                             // skip the start tag
@@ -42,8 +63,63 @@ pub fn impl_read(element: Element) -> TokenStream {
         }
 
         Element::Struct { fields, .. } => match fields {
-            Fields::Named { tag, name, fields } => named::read(&tag, quote!(#name), &fields),
-            Fields::Newtype { name, ty, .. } => newtype::read(&ty, quote!(#name)),
+            Fields::Named {
+                tag,
+                name,
+                fields,
+                deny_unknown,
+                ordered,
+            } => named::read(&tag, quote!(#name), &fields, deny_unknown, ordered),
+            Fields::Newtype { name, tags, ty } => newtype::read(&tags, &ty, quote!(#name)),
+            Fields::Text { .. } | Fields::Flatten { .. } => unreachable!(),
         },
     }
 }
+
+pub fn impl_extend(element: &Element) -> Option<TokenStream> {
+    match element {
+        Element::Struct {
+            fields: Fields::Newtype { name, tags, ty },
+            ..
+        } => newtype::extend(tags, ty, quote!(#name)),
+        _ => None,
+    }
+}
+
+pub fn impl_read_flatten(element: Element) -> TokenStream {
+    match element {
+        Element::Struct {
+            fields:
+                Fields::Flatten {
+                    name,
+                    fields,
+                    types,
+                },
+            ..
+        } => named::read_flatten(quote!(#name), &fields, &types),
+        _ => unreachable!(),
+    }
+}
+
+pub fn impl_read_text(element: &Element) -> Option<TokenStream> {
+    match element {
+        Element::Enum {
+            name: ele_name,
+            variants,
+        } => {
+            let mut variants = variants.iter().filter_map(|variant| match variant {
+                Fields::Text { name, ty } => Some(text::read(ty, quote!(#ele_name), quote!(#name))),
+                _ => None,
+            });
+
+            let read = variants.next()?;
+
+            if variants.next().is_some() {
+                panic!("Duplicate `text` variant.");
+            }
+
+            Some(read)
+        }
+        _ => None,
+    }
+}
@@ -1,83 +1,189 @@
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Ident, LitStr};
+use quote::{quote, ToTokens};
+use syn::{Ident, Index, LitStr, Path};
 
-use crate::types::{Field, Type};
+use crate::types::{BoolFormat, Collection, DefaultValue, Field, Type};
+
+pub fn read(
+    tag: &LitStr,
+    ele_name: TokenStream,
+    fields: &[Field],
+    deny_unknown: bool,
+    ordered: bool,
+) -> TokenStream {
+    let fields = fields.iter().map(Field::for_read).collect::<Vec<_>>();
 
-pub fn read(tag: &LitStr, ele_name: TokenStream, fields: &[Field]) -> TokenStream {
     let init_fields = fields.iter().map(|field| match field {
-        Field::Attribute { bind, ty, .. }
-        | Field::Child { bind, ty, .. }
-        | Field::FlattenText { bind, ty, .. } => init_value(bind, ty),
+        Field::Attribute { bind, .. }
+        | Field::Child { bind, .. }
+        | Field::FlattenText { bind, .. }
+        | Field::Any { bind, .. }
+        | Field::AnyAttr { bind, .. }
+        | Field::Comment { bind, .. }
+        | Field::ProcessingInstruction { bind, .. }
+        | Field::Flatten { bind, .. } => {
+            let init_value = init_value(field);
+            quote! { let mut #bind = #init_value; }
+        }
+        Field::Text { bind, ty, .. } if ty.is_vec() => {
+            let init_value = init_value(field);
+            quote! { let mut #bind = #init_value; }
+        }
         Field::Text { bind, .. } => quote! { let #bind; },
+        Field::Skip { .. } => quote! {},
     });
 
-    let return_fields = fields.iter().map(|field| match field {
-        Field::Attribute {
-            name,
-            bind,
-            ty,
-            default,
-            ..
-        }
-        | Field::Child {
-            name,
-            bind,
-            ty,
-            default,
-            ..
-        }
-        | Field::FlattenText {
-            name,
-            bind,
-            ty,
-            default,
-            ..
-        } => return_value(name, bind, ty, *default, &ele_name),
-        Field::Text { name, bind, ty, .. } => return_value(name, bind, ty, false, &ele_name),
+    let return_fields = fields
+        .iter()
+        .map(|field| return_field(field, &ele_name))
+        .collect::<Vec<_>>();
+
+    let read_attr_fields = fields.iter().filter_map(|field| {
+        let bind = field.bind();
+        read_attr_field(field, &quote!(#bind), &ele_name)
     });
 
-    let read_attr_fields = fields.iter().filter_map(|field| match field {
-        Field::Attribute {
+    // children are numbered from 1 in declaration order, and `__position`
+    // is the number of the last child read when they're ordered
+    let read_child_fields = fields
+        .iter()
+        .filter(|field| {
+            matches!(
+                field,
+                Field::Child { .. } | Field::FlattenText { .. } | Field::Flatten { .. }
+            )
+        })
+        .enumerate()
+        .filter_map(|(index, field)| {
+            let bind = field.bind();
+            let check_order = if ordered {
+                check_order(field, index + 1)
+            } else {
+                quote!()
+            };
+            read_child_field(field, &quote!(#bind), &check_order, &ele_name)
+        });
+
+    let init_position = if ordered {
+        quote! { let mut __position = 0; }
+    } else {
+        quote!()
+    };
+
+    let read_text_fields = fields.iter().filter_map(|field| match field {
+        Field::Text {
             bind,
             ty,
-            tag,
             name,
+            with,
+            bool_format,
+            separator,
             ..
-        } => Some(read_attrs(&tag, &bind, &name, &ty, &ele_name)),
-        _ => None,
-    });
-
-    let read_child_fields = fields.iter().filter_map(|field| match field {
-        Field::Child {
+        } => Some(read_text(
+            tag,
             bind,
-            ty,
-            tags,
             name,
-            ..
-        } => Some(read_children(tags, bind, name, ty, &ele_name)),
+            ty,
+            from_str(ty, with.as_ref(), bool_format.as_ref(), name, &ele_name),
+            separator.as_ref(),
+            &ele_name,
+        )),
         _ => None,
     });
 
-    let read_flatten_text_fields = fields.iter().filter_map(|field| match field {
-        Field::FlattenText {
+    let is_text_element = fields
+        .iter()
+        .any(|field| matches!(field, Field::Text { .. }));
+
+    let mut read_mixed_text = fields.iter().filter_map(|field| match field {
+        Field::Child {
             bind,
             ty,
-            tag,
             name,
+            is_mixed: true,
             ..
-        } => Some(read_flatten_text(tag, bind, name, ty, &ele_name)),
+        } => Some(read_mixed_text(bind, name, ty, &ele_name)),
         _ => None,
     });
 
-    let read_text_fields = fields.iter().filter_map(|field| match field {
-        Field::Text { bind, ty, name, .. } => Some(read_text(&tag, bind, name, ty, &ele_name)),
+    let read_mixed_text = match (read_mixed_text.next(), read_mixed_text.next()) {
+        (Some(_), Some(_)) => panic!("Duplicate `mixed` field."),
+        (Some(_), None) if is_text_element => {
+            panic!("`mixed` attribute and `text` attribute is disjoint.")
+        }
+        (read, _) => read,
+    };
+
+    let mut read_comment = fields.iter().filter_map(|field| match field {
+        Field::Comment { bind, name, ty } => Some(read_misc_value(bind, name, ty, &ele_name)),
         _ => None,
     });
 
-    let is_text_element = fields
+    let read_comment = match (read_comment.next(), read_comment.next()) {
+        (Some(_), Some(_)) => panic!("Duplicate `comment` field."),
+        (read, _) => read.map(|read| {
+            quote! {
+                Token::Comment { text, .. } => {
+                    let __value = text.as_str();
+                    #read
+                }
+            }
+        }),
+    };
+
+    let (pi_targets, read_pi_fields): (Vec<_>, Vec<_>) = fields
         .iter()
-        .any(|field| matches!(field, Field::Text { .. }));
+        .filter_map(|field| match field {
+            Field::ProcessingInstruction {
+                bind,
+                name,
+                ty,
+                target,
+            } => Some((target, read_misc_value(bind, name, ty, &ele_name))),
+            _ => None,
+        })
+        .unzip();
+
+    for (i, target) in pi_targets.iter().enumerate() {
+        if pi_targets[..i].iter().any(|t| t.value() == target.value()) {
+            panic!("Duplicate `pi` field with target {:?}.", target.value());
+        }
+    }
+
+    let read_pi = if pi_targets.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            Token::ProcessingInstruction { target, content, .. } => {
+                let __value = content.map(|content| content.as_str()).unwrap_or_default();
+                match target.as_str() {
+                    #( #pi_targets => { #read_pi_fields } )*
+                    _ => (),
+                }
+            }
+        })
+    };
+
+    // comments and processing instructions are read by the same loop
+    let read_misc = if read_comment.is_some() || read_pi.is_some() {
+        if is_text_element {
+            panic!("`comment` or `pi` attribute and `text` attribute is disjoint.")
+        } else if read_mixed_text.is_some() {
+            panic!("`comment` or `pi` attribute and `mixed` attribute is disjoint.")
+        }
+
+        Some(quote! {
+            while let Some(__token) = reader.read_misc()? {
+                match __token {
+                    #read_comment
+                    #read_pi
+                    _ => (),
+                }
+            }
+        })
+    } else {
+        None
+    };
 
     let return_fields = quote! {
         let __res = #ele_name {
@@ -89,6 +195,110 @@ pub fn read(tag: &LitStr, ele_name: TokenStream, fields: &[Field]) -> TokenStrea
         return Ok(__res);
     };
 
+    let mut read_any_attr_fields = fields.iter().filter_map(|field| match field {
+        Field::AnyAttr { bind, name, ty } => Some(read_any_attr(bind, name, ty, &ele_name)),
+        _ => None,
+    });
+
+    // unknown attributes are skipped, unless there's an any_attr field
+    let read_unknown_attr = match (read_any_attr_fields.next(), read_any_attr_fields.next()) {
+        (Some(_), Some(_)) => panic!("Duplicate `any_attr` field."),
+        (Some(read), None) => read,
+        (None, _) => quote! {
+            key => {
+                // namespace declarations are never unknown
+                if (#deny_unknown || reader.is_deny_unknown())
+                    && !reader.is_namespace_declaration(key)
+                {
+                    return Err(reader.unknown_attribute(key));
+                }
+
+                strong_xml::log_skip_attribute!(#ele_name, key);
+            }
+        },
+    };
+
+    let mut read_any_fields = fields.iter().filter_map(|field| match field {
+        Field::Any { bind, name, ty } => Some(read_any(bind, name, ty, &ele_name)),
+        _ => None,
+    });
+
+    let read_any = match (read_any_fields.next(), read_any_fields.next()) {
+        (Some(_), Some(_)) => panic!("Duplicate `any` field."),
+        (Some(_), None) if is_text_element => {
+            panic!("`any` attribute and `text` attribute is disjoint.")
+        }
+        (read, _) => read,
+    };
+
+    let mut read_text_map_fields = fields.iter().filter_map(|field| match field {
+        Field::FlattenText {
+            bind,
+            name,
+            ty,
+            tag: None,
+            ..
+        } => Some(read_flatten_text_map(bind, name, ty, &ele_name)),
+        _ => None,
+    });
+
+    let read_text_map = match (read_text_map_fields.next(), read_text_map_fields.next()) {
+        (Some(_), Some(_)) => panic!("Duplicate `flatten_text` field without tag."),
+        (Some(_), None) if is_text_element => {
+            panic!("`flatten_text` attribute and `text` attribute is disjoint.")
+        }
+        (read, _) => read,
+    };
+
+    // unknown elements are skipped, unless there's an any field
+    // or a flatten_text field without tag
+    let read_unknown_child = match (read_any, read_text_map) {
+        (Some(_), Some(_)) => {
+            panic!("`any` attribute and `flatten_text` attribute without tag is disjoint.")
+        }
+        (Some(read), None) | (None, Some(read)) => read,
+        (None, None) => quote! {
+            tag => {
+                if #deny_unknown || reader.is_deny_unknown() {
+                    return Err(reader.unknown_element(tag));
+                }
+
+                strong_xml::log_skip_element!(#ele_name, tag);
+                // skip the start tag
+                reader.next();
+                reader.read_to_end(tag)?;
+            }
+        },
+    };
+
+    let read_child = quote! {
+        match __tag {
+            #( #read_child_fields, )*
+            #read_unknown_child,
+        }
+    };
+
+    // text nodes are only read if there's a mixed field, and comments
+    // and processing instructions are only read if there's a field for them
+    let read_children = if let Some(read_text) = read_mixed_text.or(read_misc) {
+        quote! {
+            loop {
+                #read_text
+
+                match reader.find_element_start(Some(#tag))? {
+                    Some(__tag) => #read_child,
+                    None => break,
+                }
+            }
+        }
+    } else {
+        quote! {
+            while let Some(__tag) = reader.find_element_start(Some(#tag))? {
+                #read_child
+            }
+        }
+    };
+
     let read_content = if is_text_element {
         quote! {
             #( #read_text_fields )*
@@ -100,18 +310,7 @@ pub fn read(tag: &LitStr, ele_name: TokenStream, fields: &[Field]) -> TokenStrea
                 #return_fields
             }
 
-            while let Some(__tag) = reader.find_element_start(Some(#tag))? {
-                match __tag {
-                    #( #read_child_fields, )*
-                    #( #read_flatten_text_fields, )*
-                    tag => {
-                        strong_xml::log_skip_element!(#ele_name, tag);
-                        // skip the start tag
-                        reader.next();
-                        reader.read_to_end(tag)?;
-                    },
-                }
-            }
+            #read_children
 
             #return_fields
         }
@@ -121,15 +320,14 @@ pub fn read(tag: &LitStr, ele_name: TokenStream, fields: &[Field]) -> TokenStrea
         strong_xml::log_start_reading!(#ele_name);
 
         #( #init_fields )*
+        #init_position
 
         reader.read_till_element_start(#tag)?;
 
         while let Some((__key, __value)) = reader.find_attribute()? {
             match __key {
                 #( #read_attr_fields, )*
-                key => {
-                    strong_xml::log_skip_attribute!(#ele_name, key);
-                },
+                #read_unknown_attr,
             }
         }
 
@@ -137,49 +335,461 @@ pub fn read(tag: &LitStr, ele_name: TokenStream, fields: &[Field]) -> TokenStrea
     }
 }
 
-fn init_value(name: &Ident, ty: &Type) -> TokenStream {
-    if ty.is_vec() {
-        quote! { let mut #name = Vec::new(); }
-    } else {
-        quote! { let mut #name = None; }
+/// Implements `XmlReadFlatten` for a tagless struct
+///
+/// Fields are stored in a tuple (`__state`) until `finish` is called.
+pub fn read_flatten(ele_name: TokenStream, fields: &[Field], types: &[syn::Type]) -> TokenStream {
+    let return_fields = fields
+        .iter()
+        .map(|field| return_field(field.for_read(), &ele_name))
+        .collect::<Vec<_>>();
+
+    // skipped fields are not part of the state
+    let fields = fields
+        .iter()
+        .map(Field::for_read)
+        .zip(types)
+        .filter(|(field, _)| match field {
+            Field::Text { .. } => panic!("`text` attribute can't be used in a struct without tag."),
+            Field::Child { is_mixed: true, .. } => {
+                panic!("`mixed` attribute can't be used in a struct without tag.")
+            }
+            Field::Any { .. } => panic!("`any` attribute can't be used in a struct without tag."),
+            Field::AnyAttr { .. } => {
+                panic!("`any_attr` attribute can't be used in a struct without tag.")
+            }
+            Field::Comment { .. } => {
+                panic!("`comment` attribute can't be used in a struct without tag.")
+            }
+            Field::ProcessingInstruction { .. } => {
+                panic!("`pi` attribute can't be used in a struct without tag.")
+            }
+            Field::FlattenText { tag: None, .. } => {
+                panic!(
+                    "`flatten_text` attribute without tag can't be used in a struct without tag."
+                )
+            }
+            Field::Skip { .. } => false,
+            _ => true,
+        })
+        .collect::<Vec<_>>();
+
+    let state_types = fields.iter().map(|(field, original)| match field {
+        Field::Flatten { .. } => quote! {
+            <#original as strong_xml::XmlReadFlatten<'__input>>::State
+        },
+        // arrays are collected into a Vec first
+        Field::Attribute { ty, .. } | Field::Child { ty, .. } | Field::FlattenText { ty, .. }
+            if matches!(ty.collection(), Some(Collection::Array(..))) =>
+        {
+            match original {
+                syn::Type::Array(array) => {
+                    let elem = &array.elem;
+                    quote! { Vec<#elem> }
+                }
+                _ => unreachable!(),
+            }
+        }
+        Field::Attribute { ty, .. } | Field::Child { ty, .. } | Field::FlattenText { ty, .. }
+            if ty.is_vec() || ty.is_option() || ty.is_map() =>
+        {
+            quote! { #original }
+        }
+        _ => quote! { Option<#original> },
+    });
+
+    let init_values = fields.iter().map(|(field, _)| init_value(field));
+
+    let state_binds = (0..fields.len())
+        .map(|index| {
+            let index = Index::from(index);
+            quote!(__state.#index)
+        })
+        .collect::<Vec<_>>();
+
+    let is_attribute = fields.iter().filter_map(|(field, _)| match field {
+        Field::Attribute { tag, .. } => Some(quote! { reader.is_attribute(__key, #tag) }),
+        Field::Flatten { ty, .. } => Some(quote! {
+            <#ty as strong_xml::XmlReadFlatten>::is_attribute(reader, __key)
+        }),
+        _ => None,
+    });
+
+    let is_child = fields.iter().flat_map(|(field, _)| match field {
+        Field::Child { tags, .. } => tags
+            .iter()
+            .map(|tag| quote! { reader.is_element(__tag, #tag) })
+            .collect(),
+        Field::FlattenText { tag: Some(tag), .. } => {
+            vec![quote! { reader.is_element(__tag, #tag) }]
+        }
+        Field::Flatten { ty, .. } => vec![quote! {
+            <#ty as strong_xml::XmlReadFlatten>::is_child(reader, __tag)
+        }],
+        _ => vec![],
+    });
+
+    let read_attr_fields = fields
+        .iter()
+        .zip(&state_binds)
+        .filter_map(|((field, _), bind)| read_attr_field(field, bind, &ele_name));
+
+    let read_child_fields = fields
+        .iter()
+        .zip(&state_binds)
+        .filter_map(|((field, _), bind)| read_child_field(field, bind, &quote!(), &ele_name));
+
+    let binds = fields.iter().map(|(field, _)| field.bind());
+
+    quote! {
+        type State = ( #( #state_types, )* );
+
+        fn init() -> Self::State {
+            ( #( #init_values, )* )
+        }
+
+        fn is_attribute(reader: &strong_xml::XmlReader<'__input>, __key: &str) -> bool {
+            #( #is_attribute || )* false
+        }
+
+        fn read_attribute(
+            __state: &mut Self::State,
+            reader: &mut strong_xml::XmlReader<'__input>,
+            __key: &'__input str,
+            __value: std::borrow::Cow<'__input, str>,
+        ) -> strong_xml::XmlResult<()> {
+            use strong_xml::XmlError;
+
+            match __key {
+                #( #read_attr_fields, )*
+                _ => (),
+            }
+
+            Ok(())
+        }
+
+        fn is_child(reader: &strong_xml::XmlReader<'__input>, __tag: &str) -> bool {
+            #( #is_child || )* false
+        }
+
+        fn read_child(
+            __state: &mut Self::State,
+            reader: &mut strong_xml::XmlReader<'__input>,
+            __tag: &'__input str,
+        ) -> strong_xml::XmlResult<()> {
+            use strong_xml::XmlError;
+
+            match __tag {
+                #( #read_child_fields, )*
+                _ => (),
+            }
+
+            Ok(())
+        }
+
+        fn finish(__state: Self::State) -> strong_xml::XmlResult<Self> {
+            use strong_xml::XmlError;
+
+            let ( #( #binds, )* ) = __state;
+
+            Ok(#ele_name {
+                #( #return_fields, )*
+            })
+        }
+    }
+}
+
+fn init_value(field: &Field) -> TokenStream {
+    match field {
+        Field::Flatten { ty, .. } => quote! { <#ty as strong_xml::XmlReadFlatten>::init() },
+        Field::Attribute { ty, .. }
+        | Field::Child { ty, .. }
+        | Field::FlattenText { ty, .. }
+        | Field::Any { ty, .. }
+        | Field::AnyAttr { ty, .. }
+        | Field::Text { ty, .. }
+        | Field::Comment { ty, .. }
+        | Field::ProcessingInstruction { ty, .. }
+            if ty.is_vec() =>
+        {
+            match ty.collection() {
+                Some(Collection::Vec) | Some(Collection::Array(..)) => quote! { Vec::new() },
+                Some(Collection::VecDeque) => quote! { std::collections::VecDeque::new() },
+                Some(Collection::Set(ty)) | Some(Collection::SmallVec(ty)) => {
+                    quote! { <#ty as Default>::default() }
+                }
+                Some(Collection::OptionVec) | None => quote! { None },
+            }
+        }
+        Field::AnyAttr {
+            ty: Type::T(ty), ..
+        }
+        | Field::AnyAttr {
+            ty: Type::MapT(ty, ..),
+            ..
+        }
+        | Field::Child {
+            ty: Type::MapT(ty, ..),
+            ..
+        }
+        | Field::FlattenText {
+            ty: Type::MapT(ty, ..),
+            ..
+        } => quote! { <#ty as Default>::default() },
+        _ => quote! { None },
     }
 }
 
+fn return_field(field: &Field, ele_name: &TokenStream) -> TokenStream {
+    match field {
+        Field::Attribute {
+            name,
+            bind,
+            ty,
+            default,
+            with,
+            bool_format,
+            ..
+        }
+        | Field::FlattenText {
+            name,
+            bind,
+            ty,
+            default,
+            with,
+            bool_format,
+            ..
+        } => return_value(name, bind, ty, default.as_ref(), ele_name, |value| {
+            let from_str = from_str(ty, with.as_ref(), bool_format.as_ref(), name, ele_name);
+            quote! {
+                let __value = std::borrow::Cow::Borrowed(#value);
+                #from_str
+            }
+        }),
+        Field::Child {
+            name,
+            bind,
+            ty,
+            default,
+            ..
+        } => return_value(
+            name,
+            bind,
+            ty,
+            default.as_ref(),
+            ele_name,
+            |value| match ty {
+                Type::T(ty) => quote! { <#ty as strong_xml::XmlRead>::from_str(#value)? },
+                _ => unreachable!(),
+            },
+        ),
+        Field::Text { name, bind, ty, .. } => {
+            return_value(name, bind, ty, None, ele_name, |_| unreachable!())
+        }
+        Field::Flatten { name, bind, ty } => quote! {
+            #name: <#ty as strong_xml::XmlReadFlatten>::finish(#bind)?
+        },
+        Field::AnyAttr {
+            name,
+            bind,
+            ty: Type::T(_),
+        } => quote! { #name: #bind },
+        Field::Any { name, bind, ty }
+        | Field::AnyAttr { name, bind, ty }
+        | Field::Comment { name, bind, ty }
+        | Field::ProcessingInstruction { name, bind, ty, .. } => {
+            return_value(name, bind, ty, None, ele_name, |_| unreachable!())
+        }
+        Field::Skip { name, .. } => quote! { #name: Default::default() },
+    }
+}
+
+// returns the match arm reading the attribute into `bind`
+fn read_attr_field(
+    field: &Field,
+    bind: &TokenStream,
+    ele_name: &TokenStream,
+) -> Option<TokenStream> {
+    match field {
+        Field::Attribute {
+            ty,
+            tag,
+            name,
+            with,
+            bool_format,
+            separator,
+            ..
+        } => Some(read_attrs(
+            tag,
+            bind,
+            name,
+            ty,
+            from_str(ty, with.as_ref(), bool_format.as_ref(), name, ele_name),
+            separator.as_ref(),
+            ele_name,
+        )),
+        Field::Flatten { ty, name, .. } => Some(quote! {
+            __key if <#ty as strong_xml::XmlReadFlatten>::is_attribute(reader, __key) => {
+                strong_xml::log_start_reading_field!(#ele_name, #name);
+
+                <#ty as strong_xml::XmlReadFlatten>::read_attribute(&mut #bind, reader, __key, __value)?;
+
+                strong_xml::log_finish_reading_field!(#ele_name, #name);
+            }
+        }),
+        _ => None,
+    }
+}
+
+// returns the match arm reading the child element into `bind`,
+// `check_order` is run before reading it
+fn read_child_field(
+    field: &Field,
+    bind: &TokenStream,
+    check_order: &TokenStream,
+    ele_name: &TokenStream,
+) -> Option<TokenStream> {
+    match field {
+        Field::Child {
+            ty,
+            tags,
+            name,
+            key,
+            ..
+        } => Some(read_children(
+            tags,
+            bind,
+            name,
+            ty,
+            key.as_ref(),
+            check_order,
+            ele_name,
+        )),
+        Field::FlattenText {
+            ty,
+            tag: Some(tag),
+            name,
+            with,
+            bool_format,
+            ..
+        } => Some(read_flatten_text(
+            tag,
+            bind,
+            name,
+            ty,
+            from_str(ty, with.as_ref(), bool_format.as_ref(), name, ele_name),
+            check_order,
+            ele_name,
+        )),
+        Field::Flatten { ty, name, .. } => Some(quote! {
+            __tag if <#ty as strong_xml::XmlReadFlatten>::is_child(reader, __tag) => {
+                #check_order
+
+                strong_xml::log_start_reading_field!(#ele_name, #name);
+
+                <#ty as strong_xml::XmlReadFlatten>::read_child(&mut #bind, reader, __tag)?;
+
+                strong_xml::log_finish_reading_field!(#ele_name, #name);
+            }
+        }),
+        _ => None,
+    }
+}
+
+// returns an error if the child is read after any later child, or it's
+// read again but can only appear once
+fn check_order(field: &Field, position: usize) -> TokenStream {
+    let repeatable = match field {
+        Field::Child { ty, .. } | Field::FlattenText { ty, .. } => ty.is_vec() || ty.is_map(),
+        // children of a flattened struct can appear in any order
+        _ => true,
+    };
+
+    quote! {
+        if __position > #position {
+            return Err(reader.unordered_element(__tag));
+        } else if __position == #position && !#repeatable {
+            return Err(reader.duplicate_element(__tag));
+        }
+        __position = #position;
+    }
+}
+
+// `parse_value` converts the literal of `default_value` into the field type
 fn return_value(
     name: &TokenStream,
     bind: &Ident,
     ty: &Type,
-    default: bool,
+    default: Option<&DefaultValue>,
     ele_name: &TokenStream,
+    parse_value: impl FnOnce(&LitStr) -> TokenStream,
 ) -> TokenStream {
-    if ty.is_vec() || ty.is_option() {
-        quote! { #name: #bind }
-    } else if default {
-        quote! { #name: #bind.unwrap_or_default() }
-    } else {
-        quote! {
+    if let Some(Collection::Array(ty, len)) = ty.collection() {
+        return quote! {
+            #name: match <#ty as std::convert::TryFrom<Vec<_>>>::try_from(#bind) {
+                Ok(__value) => __value,
+                Err(__values) => return Err(XmlError::LengthMismatch {
+                    name: stringify!(#ele_name).to_owned(),
+                    field: stringify!(#name).to_owned(),
+                    expected: #len,
+                    found: __values.len(),
+                }),
+            }
+        };
+    }
+
+    if ty.is_vec() || ty.is_option() || ty.is_map() {
+        return quote! { #name: #bind };
+    }
+
+    match default {
+        Some(DefaultValue::Default) => quote! { #name: #bind.unwrap_or_default() },
+        Some(DefaultValue::Fn(path)) => quote! { #name: #bind.unwrap_or_else(|| #path()) },
+        Some(DefaultValue::Value(value)) => {
+            let parse_value = parse_value(value);
+            quote! {
+                #name: match #bind {
+                    Some(__value) => __value,
+                    None => { #parse_value }
+                }
+            }
+        }
+        None => quote! {
             #name: #bind.ok_or(XmlError::MissingField {
                 name: stringify!(#ele_name).to_owned(),
                 field: stringify!(#name).to_owned(),
             })?
-        }
+        },
     }
 }
 
 fn read_attrs(
     tag: &LitStr,
-    bind: &Ident,
+    bind: &TokenStream,
     name: &TokenStream,
     ty: &Type,
+    from_str: TokenStream,
+    separator: Option<&LitStr>,
     ele_name: &TokenStream,
 ) -> TokenStream {
-    let from_str = from_str(ty);
-
     if ty.is_vec() {
-        panic!("`attr` attribute doesn't support Vec.");
+        let split_list = split_list(separator);
+        let push_value = push_value(bind, ty, from_str, name, ele_name);
+
+        quote! {
+            __key if reader.is_attribute(__key, #tag) => {
+                strong_xml::log_start_reading_field!(#ele_name, #name);
+
+                for __value in #split_list {
+                    #push_value
+                }
+
+                strong_xml::log_finish_reading_field!(#ele_name, #name);
+            }
+        }
     } else {
         quote! {
-            #tag => {
+            __key if reader.is_attribute(__key, #tag) => {
                 strong_xml::log_start_reading_field!(#ele_name, #name);
 
                 #bind = Some(#from_str);
@@ -195,12 +805,24 @@ fn read_text(
     bind: &Ident,
     name: &TokenStream,
     ty: &Type,
+    from_str: TokenStream,
+    separator: Option<&LitStr>,
     ele_name: &TokenStream,
 ) -> TokenStream {
-    let from_str = from_str(ty);
-
     if ty.is_vec() {
-        panic!("`text` attribute doesn't support Vec.");
+        let split_list = split_list(separator);
+        let push_value = push_value(bind, ty, from_str, name, ele_name);
+
+        quote! {
+            strong_xml::log_start_reading_field!(#ele_name, #name);
+
+            let __value = reader.read_text(#tag)?;
+            for __value in #split_list {
+                #push_value
+            }
+
+            strong_xml::log_finish_reading_field!(#ele_name, #name);
+        }
     } else {
         quote! {
             strong_xml::log_start_reading_field!(#ele_name, #name);
@@ -213,25 +835,68 @@ fn read_text(
     }
 }
 
+// splits `__value` into items of a list
+fn split_list(separator: Option<&LitStr>) -> TokenStream {
+    match separator {
+        Some(separator) => quote! { strong_xml::utils::split_list(__value, Some(#separator)) },
+        None => quote! { strong_xml::utils::split_list(__value, None) },
+    }
+}
+
 fn read_children(
     tags: &[LitStr],
-    bind: &Ident,
+    bind: &TokenStream,
     name: &TokenStream,
     ty: &Type,
+    key: Option<&LitStr>,
+    check_order: &TokenStream,
     ele_name: &TokenStream,
 ) -> TokenStream {
     let from_reader = match &ty {
-        Type::VecT(ty) => quote! {
-            #bind.push(<#ty as strong_xml::XmlRead>::from_reader(reader)?);
-        },
+        // the key is read from the attribute before reading the child
+        Type::MapT(_, key_ty, value_ty) => {
+            let value_ty = match &**value_ty {
+                Type::T(ty) => ty,
+                _ => panic!("`child` attribute only supports Vec<T>, Option<T>, T and maps of T."),
+            };
+            let from_str = from_str(key_ty, None, None, name, ele_name);
+
+            quote! {
+                let __key = match reader.peek_attribute(#key)? {
+                    Some(__key) => __key,
+                    None => return Err(XmlError::MissingField {
+                        name: __tag.to_owned(),
+                        field: #key.to_owned(),
+                    }),
+                };
+                let __value = __key.clone();
+                if #bind.insert(#from_str, <#value_ty as strong_xml::XmlRead>::from_reader(reader)?).is_some() {
+                    return Err(reader.duplicate_key(__tag, &__key));
+                }
+            }
+        }
+        Type::VecT(elem, _) => push_value(
+            bind,
+            ty,
+            quote! { <#elem as strong_xml::XmlRead>::from_reader(reader)? },
+            name,
+            ele_name,
+        ),
+        // a child appearing again is read into the same value,
+        // so newtypes over `Vec<T>` collect every run of siblings
         Type::OptionT(ty) | Type::T(ty) => quote! {
-            #bind = Some(<#ty as strong_xml::XmlRead>::from_reader(reader)?);
+            match &mut #bind {
+                Some(__value) => <#ty as strong_xml::XmlRead>::extend_from_reader(__value, reader)?,
+                None => #bind = Some(<#ty as strong_xml::XmlRead>::from_reader(reader)?),
+            }
         },
-        _ => panic!("`child` attribute only supports Vec<T>, Option<T> and T."),
+        _ => panic!("`child` attribute only supports Vec<T>, Option<T>, T and maps of T."),
     };
 
     quote! {
-        #( #tags )|* => {
+        __tag if #( reader.is_element(__tag, #tags) )||* => {
+            #check_order
+
             strong_xml::log_start_reading_field!(#ele_name, #name);
 
             #from_reader
@@ -241,19 +906,164 @@ fn read_children(
     }
 }
 
-fn read_flatten_text(
-    tag: &LitStr,
+fn read_mixed_text(
     bind: &Ident,
     name: &TokenStream,
     ty: &Type,
     ele_name: &TokenStream,
 ) -> TokenStream {
-    let from_str = from_str(ty);
+    let ty = match ty {
+        Type::VecT(ty, Collection::Vec) => ty,
+        _ => panic!("`mixed` attribute only supports Vec<T>."),
+    };
 
+    quote! {
+        if let Some(__value) = reader.read_mixed_text()? {
+            strong_xml::log_start_reading_field!(#ele_name, #name);
+
+            #bind.push(<#ty as strong_xml::XmlReadText>::from_text(__value)?);
+
+            strong_xml::log_finish_reading_field!(#ele_name, #name);
+        }
+    }
+}
+
+fn read_any_attr(
+    bind: &Ident,
+    name: &TokenStream,
+    ty: &Type,
+    ele_name: &TokenStream,
+) -> TokenStream {
+    let insert = if ty.is_vec() {
+        push_value(
+            bind,
+            ty,
+            quote! { (__key.into(), __value.into()) },
+            name,
+            ele_name,
+        )
+    } else {
+        quote! { #bind.insert(__key.into(), __value.into()); }
+    };
+
+    // namespace declarations are emitted by the writer when needed,
+    // and prefixed keys are captured as `{uri}local`
+    quote! {
+        _ if reader.is_namespace_declaration(__key) => {}
+        _ => {
+            strong_xml::log_start_reading_field!(#ele_name, #name);
+
+            let __key = reader.expand_attribute(__key);
+
+            #insert
+
+            strong_xml::log_finish_reading_field!(#ele_name, #name);
+        }
+    }
+}
+
+// reads children which don't match any other field, keyed by their tags
+fn read_flatten_text_map(
+    bind: &Ident,
+    name: &TokenStream,
+    ty: &Type,
+    ele_name: &TokenStream,
+) -> TokenStream {
+    let (read_key, read_value) = match ty {
+        Type::MapT(_, key_ty, value_ty) => (
+            from_str(key_ty, None, None, name, ele_name),
+            from_str(value_ty, None, None, name, ele_name),
+        ),
+        _ => panic!("`flatten_text` attribute without tag only supports maps."),
+    };
+
+    quote! {
+        __tag => {
+            strong_xml::log_start_reading_field!(#ele_name, #name);
+
+            // skip element start
+            reader.next();
+
+            let __key = {
+                let __value = std::borrow::Cow::Borrowed(__tag);
+                #read_key
+            };
+            let __value = reader.read_text(__tag)?;
+            if #bind.insert(__key, #read_value).is_some() {
+                return Err(reader.duplicate_key(__tag, __tag));
+            }
+
+            strong_xml::log_finish_reading_field!(#ele_name, #name);
+        }
+    }
+}
+
+fn read_any(bind: &Ident, name: &TokenStream, ty: &Type, ele_name: &TokenStream) -> TokenStream {
+    let push_value = push_value(
+        bind,
+        ty,
+        from_str(ty, None, None, name, ele_name),
+        name,
+        ele_name,
+    );
+
+    quote! {
+        _ => {
+            strong_xml::log_start_reading_field!(#ele_name, #name);
+
+            let __value = reader.read_raw_element()?;
+            #push_value
+
+            strong_xml::log_finish_reading_field!(#ele_name, #name);
+        }
+    }
+}
+
+// reads the content of a comment or processing instruction from `__value`
+fn read_misc_value(
+    bind: &Ident,
+    name: &TokenStream,
+    ty: &Type,
+    ele_name: &TokenStream,
+) -> TokenStream {
+    let from_str = from_str(ty, None, None, name, ele_name);
+
+    // only the first one is kept if it's an Option
+    let read_value = if ty.is_vec() {
+        push_value(bind, ty, from_str, name, ele_name)
+    } else {
+        quote! {
+            if #bind.is_none() {
+                #bind = Some(#from_str);
+            }
+        }
+    };
+
+    quote! {
+        strong_xml::log_start_reading_field!(#ele_name, #name);
+
+        let __value = std::borrow::Cow::Borrowed(__value);
+        #read_value
+
+        strong_xml::log_finish_reading_field!(#ele_name, #name);
+    }
+}
+
+fn read_flatten_text(
+    tag: &LitStr,
+    bind: &TokenStream,
+    name: &TokenStream,
+    ty: &Type,
+    from_str: TokenStream,
+    check_order: &TokenStream,
+    ele_name: &TokenStream,
+) -> TokenStream {
     let read_text = if ty.is_vec() {
+        let push_value = push_value(bind, ty, from_str, name, ele_name);
+
         quote! {
             let __value = reader.read_text(#tag)?;
-            #bind.push(#from_str);
+            #push_value
         }
     } else {
         quote! {
@@ -263,7 +1073,9 @@ fn read_flatten_text(
     };
 
     quote! {
-        #tag => {
+        __tag if reader.is_element(__tag, #tag) => {
+            #check_order
+
             // skip element start
             reader.next();
 
@@ -276,18 +1088,83 @@ fn read_flatten_text(
     }
 }
 
-fn from_str(ty: &Type) -> TokenStream {
+// pushes `value` into the repeated field `bind`
+fn push_value(
+    bind: &impl ToTokens,
+    ty: &Type,
+    value: TokenStream,
+    name: &TokenStream,
+    ele_name: &TokenStream,
+) -> TokenStream {
+    match ty.collection() {
+        Some(Collection::VecDeque) => quote! { #bind.push_back(#value); },
+        Some(Collection::Set(_)) => quote! {
+            if !#bind.insert(#value) {
+                return Err(XmlError::DuplicateValue {
+                    name: stringify!(#ele_name).to_owned(),
+                    field: stringify!(#name).to_owned(),
+                });
+            }
+        },
+        Some(Collection::OptionVec) => quote! {
+            #bind.get_or_insert_with(Vec::new).push(#value);
+        },
+        _ => quote! { #bind.push(#value); },
+    }
+}
+
+pub fn from_str(
+    ty: &Type,
+    with: Option<&Path>,
+    bool_format: Option<&BoolFormat>,
+    name: &TokenStream,
+    ele_name: &TokenStream,
+) -> TokenStream {
+    if let Some(with) = with {
+        return quote! {
+            #with::from_xml(&__value).map_err(|e| XmlError::FromStr(e.into()))?
+        };
+    }
+
+    if let Some(BoolFormat {
+        true_values,
+        false_values,
+    }) = bool_format
+    {
+        return quote! {
+            match &*__value {
+                #( #true_values )|* => true,
+                #( #false_values )|* => false,
+                _ => return Err(XmlError::UnknownValue {
+                    name: "bool".to_owned(),
+                    value: __value.to_string(),
+                }),
+            }
+        };
+    }
+
     match &ty {
-        Type::CowStr | Type::OptionCowStr | Type::VecCowStr => quote! { __value },
-        Type::Bool | Type::OptionBool | Type::VecBool => quote! {
+        Type::CowStr | Type::OptionCowStr | Type::VecCowStr(_) => quote! { __value },
+        // unescaped or concatenated text can't be borrowed from the input
+        Type::Str | Type::OptionStr | Type::VecStr(_) => quote! {
+            match __value {
+                std::borrow::Cow::Borrowed(__value) => __value,
+                std::borrow::Cow::Owned(_) => {
+                    return Err(reader.cannot_borrow(stringify!(#ele_name), stringify!(#name)))
+                }
+            }
+        },
+        Type::Bool | Type::OptionBool | Type::VecBool(_) => quote! {
             match &*__value {
                 "t" | "true" | "y" | "yes" | "on" | "1" => true,
                 "f" | "false" | "n" | "no" | "off" | "0" => false,
                 _ => <bool as std::str::FromStr>::from_str(&__value).map_err(|e| XmlError::FromStr(e.into()))?
             }
         },
-        Type::T(ty) | Type::OptionT(ty) | Type::VecT(ty) => quote! {
+        Type::T(ty) | Type::OptionT(ty) | Type::VecT(ty, _) => quote! {
             <#ty as std::str::FromStr>::from_str(&__value).map_err(|e| XmlError::FromStr(e.into()))?
         },
+        // entries of maps are converted one by one
+        Type::MapT(..) => unreachable!(),
     }
 }
@@ -1,21 +1,66 @@
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::LitStr;
 
-use crate::types::Type;
+use crate::types::{Collection, Type};
 
-pub fn read(ty: &Type, ele_name: TokenStream) -> TokenStream {
-    let ty = match ty {
-        Type::T(ty) => ty,
-        _ => panic!("strong-xml only supports newtype_struct and newtype_enum for now."),
+pub fn read(tags: &[LitStr], ty: &Type, ele_name: TokenStream) -> TokenStream {
+    let read = match ty {
+        Type::T(ty) => quote! { <#ty as XmlRead>::from_reader(reader)? },
+        // sibling elements are read until one with another tag
+        Type::VecT(ty, Collection::Vec) => quote! {{
+            let mut __res = Vec::new();
+            while let Some(__tag) = reader.peek_element_start()? {
+                if #( reader.is_element(__tag, #tags) )||* {
+                    __res.push(<#ty as XmlRead>::from_reader(reader)?);
+                } else {
+                    break;
+                }
+            }
+            __res
+        }},
+        Type::OptionT(ty) => quote! {
+            match reader.peek_element_start()? {
+                Some(__tag) if #( reader.is_element(__tag, #tags) )||* => {
+                    Some(<#ty as XmlRead>::from_reader(reader)?)
+                }
+                _ => None,
+            }
+        },
+        _ => panic!("strong-xml only supports newtype over T, Option<T> and Vec<T> for now."),
     };
 
     quote! {
         strong_xml::log_start_reading!(#ele_name);
 
-        let res = <#ty as XmlRead>::from_reader(reader)?;
+        let res = #read;
 
         strong_xml::log_finish_reading!(#ele_name);
 
         return Ok(#ele_name(res));
     }
 }
+
+/// Appends the next run of siblings to a newtype struct over `Vec<T>`.
+pub fn extend(tags: &[LitStr], ty: &Type, ele_name: TokenStream) -> Option<TokenStream> {
+    let ty = match ty {
+        Type::VecT(ty, Collection::Vec) => ty,
+        _ => return None,
+    };
+
+    Some(quote! {
+        strong_xml::log_start_reading!(#ele_name);
+
+        while let Some(__tag) = reader.peek_element_start()? {
+            if #( reader.is_element(__tag, #tags) )||* {
+                self.0.push(<#ty as strong_xml::XmlRead>::from_reader(reader)?);
+            } else {
+                break;
+            }
+        }
+
+        strong_xml::log_finish_reading!(#ele_name);
+
+        Ok(())
+    })
+}
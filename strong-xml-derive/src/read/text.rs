@@ -0,0 +1,38 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::named::from_str;
+use crate::types::Type;
+
+pub fn read(ty: &Type, ele_name: TokenStream, name: TokenStream) -> TokenStream {
+    if ty.is_vec() || ty.is_option() {
+        panic!("`text` variant doesn't support Vec and Option.");
+    }
+
+    let from_str = match ty {
+        // there's no reader to get the original text from
+        Type::Str => quote! {
+            match __value {
+                std::borrow::Cow::Borrowed(__value) => __value,
+                std::borrow::Cow::Owned(__value) => {
+                    return Err(XmlError::CannotBorrow {
+                        name: stringify!(#ele_name).to_owned(),
+                        field: stringify!(#name).to_owned(),
+                        value: __value,
+                    })
+                }
+            }
+        },
+        _ => from_str(ty, None, None, &name, &ele_name),
+    };
+
+    quote! {
+        strong_xml::log_start_reading!(#ele_name::#name);
+
+        let __res = #ele_name::#name(#from_str);
+
+        strong_xml::log_finish_reading!(#ele_name::#name);
+
+        Ok(__res)
+    }
+}
@@ -1,9 +1,10 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::{Lit::*, Meta::*, *};
+use syn::{spanned::Spanned, Lit::*, Meta::*, *};
 
 use crate::utils::elide_type_lifetimes;
 
+#[allow(clippy::large_enum_variant)]
 pub enum Element {
     Struct { name: Ident, fields: Fields },
     Enum { name: Ident, variants: Vec<Fields> },
@@ -31,6 +32,8 @@ pub enum Fields {
         tag: LitStr,
         name: Ident,
         fields: Vec<Field>,
+        deny_unknown: bool,
+        ordered: bool,
     },
     /// Newtype struct or newtype variant
     ///
@@ -50,6 +53,29 @@ pub enum Fields {
         name: Ident,
         ty: Type,
     },
+    /// Text variant of an enum, which is used by mixed content
+    ///
+    /// ```ignore
+    /// enum Foo {
+    ///     #[xml(text)]
+    ///     $name($ty)
+    /// }
+    /// ```
+    Text { name: Ident, ty: Type },
+    /// Named fields of a struct without tag, which can only
+    /// be flattened into other elements
+    ///
+    /// ```ignore
+    /// struct $name {
+    ///     $( $fields )*
+    /// }
+    /// ```
+    Flatten {
+        name: Ident,
+        fields: Vec<Field>,
+        // original types of fields, with lifetimes
+        types: Vec<syn::Type>,
+    },
 }
 
 pub enum Field {
@@ -57,7 +83,7 @@ pub enum Field {
     ///
     /// ```ignore
     /// struct Foo {
-    ///     #[xml(attr = "$tag", $default)]
+    ///     #[xml(attr = "$tag", $default, $with, $bool, $separator)]
     ///     $name: $ty,
     /// }
     /// ```
@@ -66,13 +92,17 @@ pub enum Field {
         bind: Ident,
         ty: Type,
         tag: LitStr,
-        default: bool,
+        default: Option<DefaultValue>,
+        with: Option<syn::Path>,
+        bool_format: Option<BoolFormat>,
+        // separator of list items if it's a Vec
+        separator: Option<LitStr>,
     },
     /// Child(ren) Field
     ///
     /// ```ignore
     /// struct Foo {
-    ///     #[xml(child = "$tag", child = "$tag", $default)]
+    ///     #[xml(child = "$tag", child = "$tag", $default, $mixed, $key)]
     ///     $name: $ty,
     /// }
     /// ```
@@ -80,14 +110,17 @@ pub enum Field {
         name: TokenStream,
         bind: Ident,
         ty: Type,
-        default: bool,
+        default: Option<DefaultValue>,
         tags: Vec<LitStr>,
+        is_mixed: bool,
+        // attribute of children used as the key if it's a map
+        key: Option<LitStr>,
     },
     /// Text Field
     ///
     /// ```ignore
     /// struct Foo {
-    ///     #[xml(text, $default)]
+    ///     #[xml(text, $default, $with, $bool, $separator)]
     ///     $name: $ty,
     /// }
     /// ```
@@ -96,23 +129,193 @@ pub enum Field {
         bind: Ident,
         ty: Type,
         is_cdata: bool,
+        with: Option<syn::Path>,
+        bool_format: Option<BoolFormat>,
+        // separator of list items if it's a Vec
+        separator: Option<LitStr>,
     },
     /// Flatten Text
     ///
     /// ```ignore
     /// struct Foo {
-    ///     #[xml(flatten_text = "$tag", $default)]
+    ///     #[xml(flatten_text = "$tag", $default, $with, $bool)]
     ///     $name: $ty,
+    ///     #[xml(flatten_text)]
+    ///     $name: $map,
     /// }
     /// ```
     FlattenText {
         name: TokenStream,
         bind: Ident,
         ty: Type,
-        default: bool,
-        tag: LitStr,
+        default: Option<DefaultValue>,
+        // `None` if it's a map keyed by tags of children
+        tag: Option<LitStr>,
         is_cdata: bool,
+        with: Option<syn::Path>,
+        bool_format: Option<BoolFormat>,
+    },
+    /// Skipped Field
+    ///
+    /// ```ignore
+    /// struct Foo {
+    ///     #[xml(skip)]
+    ///     $name: $ty,
+    ///     #[xml(skip_read, ...)]
+    ///     $name: $ty,
+    ///     #[xml(skip_write, ...)]
+    ///     $name: $ty,
+    /// }
+    /// ```
+    Skip {
+        name: TokenStream,
+        bind: Ident,
+        // field to read if it's only skipped when writing
+        read: Option<Box<Field>>,
+        // field to write if it's only skipped when reading
+        write: Option<Box<Field>>,
+    },
+    /// Unknown Attributes Field
+    ///
+    /// ```ignore
+    /// struct Foo {
+    ///     #[xml(any_attr)]
+    ///     $name: $ty,
+    /// }
+    /// ```
+    AnyAttr {
+        name: TokenStream,
+        bind: Ident,
+        ty: Type,
+    },
+    /// Unknown Children Field
+    ///
+    /// ```ignore
+    /// struct Foo {
+    ///     #[xml(any)]
+    ///     $name: $ty,
+    /// }
+    /// ```
+    Any {
+        name: TokenStream,
+        bind: Ident,
+        ty: Type,
     },
+    /// Comments Field
+    ///
+    /// ```ignore
+    /// struct Foo {
+    ///     #[xml(comment)]
+    ///     $name: $ty,
+    /// }
+    /// ```
+    Comment {
+        name: TokenStream,
+        bind: Ident,
+        ty: Type,
+    },
+    /// Processing Instructions Field
+    ///
+    /// ```ignore
+    /// struct Foo {
+    ///     #[xml(pi = "$target")]
+    ///     $name: $ty,
+    /// }
+    /// ```
+    ProcessingInstruction {
+        name: TokenStream,
+        bind: Ident,
+        ty: Type,
+        target: LitStr,
+    },
+    /// Flattened Field
+    ///
+    /// ```ignore
+    /// struct Foo {
+    ///     #[xml(flatten)]
+    ///     $name: $ty,
+    /// }
+    /// ```
+    Flatten {
+        name: TokenStream,
+        bind: Ident,
+        ty: syn::Type,
+    },
+}
+
+/// Value used when the field is not present
+pub enum DefaultValue {
+    // #[xml(default)]
+    Default,
+    // #[xml(default = "$path")]
+    Fn(syn::Path),
+    // #[xml(default_value = "$value")]
+    Value(LitStr),
+}
+
+/// Strings used for a bool field
+///
+/// ```ignore
+/// #[xml(bool = "$format", strict_bool)]
+/// ```
+pub struct BoolFormat {
+    // strings read as `true`, the first one is written
+    pub true_values: Vec<String>,
+    // strings read as `false`, the first one is written
+    pub false_values: Vec<String>,
+}
+
+impl BoolFormat {
+    // strings accepted when reading in lenient mode
+    const LENIENT_TRUE_VALUES: [&'static str; 6] = ["t", "true", "y", "yes", "on", "1"];
+    const LENIENT_FALSE_VALUES: [&'static str; 6] = ["f", "false", "n", "no", "off", "0"];
+
+    fn parse(format: Option<&LitStr>, strict: bool) -> BoolFormat {
+        let format = format.map(LitStr::value);
+
+        let (true_values, false_values, strict): (&[&str], &[&str], _) =
+            match format.as_deref() {
+                None | Some("true_false") => (&["true"], &["false"], strict),
+                Some("numeric") => (&["1"], &["0"], strict),
+                Some("yes_no") => (&["yes"], &["no"], strict),
+                Some("strict_xsd") => (&["true", "1"], &["false", "0"], true),
+                Some(format) => match format.split_once('|') {
+                    Some((t, f)) if !t.is_empty() && !f.is_empty() && t != f => {
+                        (&[t], &[f], strict)
+                    }
+                    _ => panic!(
+                        "Unknown bool format {:?}, expected `true_false`, `numeric`, `yes_no`, `strict_xsd` or `true|false`.",
+                        format
+                    ),
+                },
+            };
+
+        let mut format = BoolFormat {
+            true_values: true_values.iter().map(|&v| v.to_owned()).collect(),
+            false_values: false_values.iter().map(|&v| v.to_owned()).collect(),
+        };
+
+        // other strings are rejected in strict mode
+        if !strict {
+            let is_new = |format: &BoolFormat, value: &str| {
+                !format.true_values.iter().any(|v| v == value)
+                    && !format.false_values.iter().any(|v| v == value)
+            };
+
+            for value in Self::LENIENT_TRUE_VALUES {
+                if is_new(&format, value) {
+                    format.true_values.push(value.to_owned());
+                }
+            }
+            for value in Self::LENIENT_FALSE_VALUES {
+                if is_new(&format, value) {
+                    format.false_values.push(value.to_owned());
+                }
+            }
+        }
+
+        format
+    }
 }
 
 pub enum Type {
@@ -121,45 +324,161 @@ pub enum Type {
     // Option<Cow<'a, str>>
     OptionCowStr,
     // Vec<Cow<'a, str>>
-    VecCowStr,
+    VecCowStr(Collection),
+    // &'a str
+    Str,
+    // Option<&'a str>
+    OptionStr,
+    // Vec<&'a str>
+    VecStr(Collection),
     // T
     T(syn::Type),
     // Option<T>
     OptionT(syn::Type),
     // Vec<T>
-    VecT(syn::Type),
+    VecT(syn::Type, Collection),
     // bool
     Bool,
     // Vec<bool>
-    VecBool,
+    VecBool(Collection),
     // Option<bool>
     OptionBool,
+    // HashMap<K, V>, BTreeMap<K, V> or IndexMap<K, V>, and types of K and V
+    MapT(syn::Type, Box<Type>, Box<Type>),
+}
+
+/// Collection of a repeated field, which is a `Vec` unless specified
+pub enum Collection {
+    // Vec<T>
+    Vec,
+    // VecDeque<T>
+    VecDeque,
+    // BTreeSet<T> or HashSet<T>, duplicate values are rejected
+    Set(syn::Type),
+    // SmallVec<[T; N]>, only recognized with the `smallvec` feature
+    SmallVec(syn::Type),
+    // [T; N], values are collected into a Vec and the length is checked
+    Array(syn::Type, syn::Expr),
+    // Option<Vec<T>>, which is `None` if there's no values
+    OptionVec,
 }
 
 impl Element {
+    /// Returns `true` if it's a struct without tag
+    pub fn is_flatten(&self) -> bool {
+        matches!(
+            self,
+            Element::Struct {
+                fields: Fields::Flatten { .. },
+                ..
+            }
+        )
+    }
+
     pub fn parse(input: DeriveInput) -> Element {
         match input.data {
             Data::Struct(data) => Element::Struct {
                 name: input.ident.clone(),
-                fields: Fields::parse(data.fields, input.attrs, input.ident),
-            },
-            Data::Enum(data) => Element::Enum {
-                name: input.ident,
-                variants: data
-                    .variants
-                    .into_iter()
-                    .map(|variant| Fields::parse(variant.fields, variant.attrs, variant.ident))
-                    .collect::<Vec<_>>(),
+                fields: match Fields::parse(
+                    data.fields,
+                    input.attrs,
+                    input.ident,
+                    None,
+                    false,
+                    false,
+                ) {
+                    Fields::Text { .. } => {
+                        panic!("`text` attribute can only be used on enum variants.")
+                    }
+                    fields => fields,
+                },
             },
+            Data::Enum(data) => {
+                // `ns`, `ignore_prefix`, `deny_unknown` and `ordered`
+                // attribute of the enum applies to all of its variants
+                let mut ns = None;
+                let mut deny_unknown = false;
+                let mut ordered = false;
+
+                for meta in input.attrs.into_iter().filter_map(get_xml_meta).flatten() {
+                    match meta {
+                        NestedMeta::Meta(NameValue(m)) if m.path.is_ident("ns") => {
+                            if let Str(lit) = m.lit {
+                                if ns.is_some() {
+                                    panic!("Duplicate `ns` attribute.");
+                                } else {
+                                    ns = Some(lit);
+                                }
+                            } else {
+                                panic!("Expected a string literal.");
+                            }
+                        }
+                        NestedMeta::Meta(Path(p)) if p.is_ident("ignore_prefix") => {
+                            if ns.is_some() {
+                                panic!("`ignore_prefix` attribute and `ns` attribute is disjoint.");
+                            } else {
+                                ns = Some(LitStr::new("*", p.span()));
+                            }
+                        }
+                        NestedMeta::Meta(Path(p)) if p.is_ident("deny_unknown") => {
+                            if deny_unknown {
+                                panic!("Duplicate `deny_unknown` attribute.");
+                            } else {
+                                deny_unknown = true;
+                            }
+                        }
+                        NestedMeta::Meta(Path(p)) if p.is_ident("ordered") => {
+                            if ordered {
+                                panic!("Duplicate `ordered` attribute.");
+                            } else {
+                                ordered = true;
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+
+                Element::Enum {
+                    name: input.ident,
+                    variants: data
+                        .variants
+                        .into_iter()
+                        .map(|variant| {
+                            match Fields::parse(
+                                variant.fields,
+                                variant.attrs,
+                                variant.ident,
+                                ns.clone(),
+                                deny_unknown,
+                                ordered,
+                            ) {
+                                Fields::Flatten { .. } => panic!("Missing `tag` attribute."),
+                                fields => fields,
+                            }
+                        })
+                        .collect::<Vec<_>>(),
+                }
+            }
             Data::Union(_) => panic!("strong-xml doesn't support Union."),
         }
     }
 }
 
 impl Fields {
-    pub fn parse(fields: syn::Fields, attrs: Vec<Attribute>, name: Ident) -> Fields {
-        // Finding `tag` attribute
+    pub fn parse(
+        fields: syn::Fields,
+        attrs: Vec<Attribute>,
+        name: Ident,
+        mut ns: Option<LitStr>,
+        mut deny_unknown: bool,
+        mut ordered: bool,
+    ) -> Fields {
+        // Finding `tag`, `ns`, `text`, `deny_unknown` and `ordered` attribute
         let mut tags = Vec::new();
+        let mut has_ns = false;
+        let mut is_text = false;
+        let mut has_deny_unknown = false;
+        let mut has_ordered = false;
 
         for meta in attrs.into_iter().filter_map(get_xml_meta).flatten() {
             match meta {
@@ -170,30 +489,128 @@ impl Fields {
                         panic!("Expected a string literal.");
                     }
                 }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("ns") => {
+                    if let Str(lit) = m.lit {
+                        if has_ns {
+                            panic!("Duplicate `ns` attribute.");
+                        } else {
+                            has_ns = true;
+                            ns = Some(lit);
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("deny_unknown") => {
+                    if has_deny_unknown {
+                        panic!("Duplicate `deny_unknown` attribute.");
+                    } else {
+                        has_deny_unknown = true;
+                        deny_unknown = true;
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("ordered") => {
+                    if has_ordered {
+                        panic!("Duplicate `ordered` attribute.");
+                    } else {
+                        has_ordered = true;
+                        ordered = true;
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("text") => {
+                    if is_text {
+                        panic!("Duplicate `text` attribute.");
+                    } else {
+                        is_text = true;
+                    }
+                }
+                // ignoring prefixes is the same as matching any namespace
+                NestedMeta::Meta(Path(p)) if p.is_ident("ignore_prefix") => {
+                    if has_ns {
+                        panic!("`ignore_prefix` attribute and `ns` attribute is disjoint.");
+                    } else {
+                        has_ns = true;
+                        ns = Some(LitStr::new("*", p.span()));
+                    }
+                }
                 _ => (),
             }
         }
 
+        if is_text {
+            if !tags.is_empty() {
+                panic!("`text` attribute and `tag` attribute is disjoint.");
+            }
+
+            return match fields {
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Fields::Text {
+                    name,
+                    ty: Type::parse(fields.unnamed.into_iter().next().unwrap().ty),
+                },
+                _ => panic!("`text` attribute can only be used on newtype variants."),
+            };
+        }
+
         if tags.is_empty() {
+            // named struct without tag can be flattened into other elements
+            if let syn::Fields::Named(fields) = fields {
+                return Fields::Flatten {
+                    name,
+                    types: fields.named.iter().map(|field| field.ty.clone()).collect(),
+                    fields: fields
+                        .named
+                        .into_iter()
+                        .map(|field| {
+                            let name = field.ident.clone().unwrap();
+                            let bind = format_ident!("__self_{}", name);
+                            Field::parse(quote!(#name), bind, field, ns.as_ref())
+                        })
+                        .collect(),
+                };
+            }
+
             panic!("Missing `tag` attribute.");
         }
 
+        let mut tags = tags
+            .into_iter()
+            .map(|tag| qualify(ns.as_ref(), tag))
+            .collect::<Vec<_>>();
+
         match fields {
             syn::Fields::Unit => Fields::Named {
                 name,
                 tag: tags.remove(0),
                 fields: Vec::new(),
+                deny_unknown,
+                ordered,
             },
             syn::Fields::Unnamed(fields) => {
                 // we will assume it's a newtype stuct/enum
                 // if it has only one field and no field attribute
                 if fields.unnamed.len() == 1 {
-                    let field = fields.unnamed.first().unwrap().clone();
-                    if field.attrs.into_iter().filter_map(get_xml_meta).count() == 0 {
-                        return Fields::Newtype {
+                    let mut field = fields.unnamed.first().unwrap().clone();
+                    if field.attrs.iter().cloned().filter_map(get_xml_meta).count() == 0 {
+                        let ty = Type::parse(field.ty.clone());
+
+                        if !ty.is_text() {
+                            return Fields::Newtype { name, tags, ty };
+                        }
+
+                        // newtype over text is the same as a `text` field
+                        field.attrs.push(parse_quote!(#[xml(text)]));
+
+                        return Fields::Named {
                             name,
-                            tags,
-                            ty: Type::parse(field.ty),
+                            tag: tags.remove(0),
+                            fields: vec![Field::parse(
+                                quote!(0),
+                                format_ident!("__self_0"),
+                                field,
+                                ns.as_ref(),
+                            )],
+                            deny_unknown,
+                            ordered,
                         };
                     }
                 }
@@ -208,9 +625,11 @@ impl Fields {
                         .map(|(index, field)| {
                             let index = syn::Index::from(index);
                             let bind = format_ident!("__self_{}", index);
-                            Field::parse(quote!(#index), bind, field)
+                            Field::parse(quote!(#index), bind, field, ns.as_ref())
                         })
                         .collect::<Vec<_>>(),
+                    deny_unknown,
+                    ordered,
                 }
             }
             syn::Fields::Named(_) => Fields::Named {
@@ -221,30 +640,214 @@ impl Fields {
                     .map(|field| {
                         let name = field.ident.clone().unwrap();
                         let bind = format_ident!("__self_{}", name);
-                        Field::parse(quote!(#name), bind, field)
+                        Field::parse(quote!(#name), bind, field, ns.as_ref())
                     })
                     .collect::<Vec<_>>(),
+                deny_unknown,
+                ordered,
             },
         }
     }
 }
 
 impl Field {
-    pub fn parse(name: TokenStream, bind: Ident, field: syn::Field) -> Field {
-        let mut default = false;
+    pub fn parse(
+        name: TokenStream,
+        bind: Ident,
+        field: syn::Field,
+        element_ns: Option<&LitStr>,
+    ) -> Field {
+        let mut default = None;
+        let mut ns = None;
+        let mut with = None;
         let mut attr_tag = None;
         let mut child_tags = Vec::new();
         let mut is_text = false;
         let mut flatten_text_tag = None;
         let mut is_cdata = false;
+        let mut skip = false;
+        let mut skip_read = false;
+        let mut skip_write = false;
+        let mut is_flatten = false;
+        let mut is_mixed = false;
+        let mut is_any = false;
+        let mut is_any_attr = false;
+        let mut is_comment = false;
+        let mut pi_target = None;
+        let mut separator = None;
+        let mut bool_format = None;
+        let mut strict_bool = false;
+        let mut key = None;
 
         for meta in field.attrs.into_iter().filter_map(get_xml_meta).flatten() {
             match meta {
                 NestedMeta::Meta(Path(p)) if p.is_ident("default") => {
-                    if default {
+                    if default.is_some() {
                         panic!("Duplicate `default` attribute.");
                     } else {
-                        default = true;
+                        default = Some(DefaultValue::Default);
+                    }
+                }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("default") => {
+                    if let Str(lit) = m.lit {
+                        if default.is_some() {
+                            panic!("Duplicate `default` attribute.");
+                        } else {
+                            default = Some(DefaultValue::Fn(
+                                lit.parse::<syn::Path>()
+                                    .unwrap_or_else(|_| panic!("Expected a path.")),
+                            ));
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("default_value") => {
+                    if let Str(lit) = m.lit {
+                        if default.is_some() {
+                            panic!(
+                                "`default_value` attribute and `default` attribute is disjoint."
+                            );
+                        } else {
+                            default = Some(DefaultValue::Value(lit));
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("skip") => {
+                    if skip {
+                        panic!("Duplicate `skip` attribute.");
+                    } else {
+                        skip = true;
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("any") => {
+                    if is_any {
+                        panic!("Duplicate `any` attribute.");
+                    } else {
+                        is_any = true;
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("any_attr") => {
+                    if is_any_attr {
+                        panic!("Duplicate `any_attr` attribute.");
+                    } else {
+                        is_any_attr = true;
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("comment") => {
+                    if is_comment {
+                        panic!("Duplicate `comment` attribute.");
+                    } else if pi_target.is_some() {
+                        panic!("`comment` attribute and `pi` attribute is disjoint.");
+                    } else {
+                        is_comment = true;
+                    }
+                }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("pi") => {
+                    if let Str(lit) = m.lit {
+                        if pi_target.is_some() {
+                            panic!("Duplicate `pi` attribute.");
+                        } else if is_comment {
+                            panic!("`pi` attribute and `comment` attribute is disjoint.");
+                        } else {
+                            pi_target = Some(lit);
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("mixed") => {
+                    if is_mixed {
+                        panic!("Duplicate `mixed` attribute.");
+                    } else {
+                        is_mixed = true;
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("flatten") => {
+                    if is_flatten {
+                        panic!("Duplicate `flatten` attribute.");
+                    } else {
+                        is_flatten = true;
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("skip_read") => {
+                    if skip_read {
+                        panic!("Duplicate `skip_read` attribute.");
+                    } else {
+                        skip_read = true;
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("skip_write") => {
+                    if skip_write {
+                        panic!("Duplicate `skip_write` attribute.");
+                    } else {
+                        skip_write = true;
+                    }
+                }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("ns") => {
+                    if let Str(lit) = m.lit {
+                        if ns.is_some() {
+                            panic!("Duplicate `ns` attribute.");
+                        } else if is_text {
+                            panic!("`ns` attribute and `text` attribute is disjoint.");
+                        } else {
+                            ns = Some(lit);
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("separator") => {
+                    if let Str(lit) = m.lit {
+                        if separator.is_some() {
+                            panic!("Duplicate `separator` attribute.");
+                        } else if lit.value().is_empty() {
+                            panic!("`separator` attribute should not be empty.");
+                        } else {
+                            separator = Some(lit);
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("bool") => {
+                    if let Str(lit) = m.lit {
+                        if bool_format.is_some() {
+                            panic!("Duplicate `bool` attribute.");
+                        } else if with.is_some() {
+                            panic!("`bool` attribute and `with` attribute is disjoint.");
+                        } else {
+                            bool_format = Some(lit);
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                NestedMeta::Meta(Path(p)) if p.is_ident("strict_bool") => {
+                    if strict_bool {
+                        panic!("Duplicate `strict_bool` attribute.");
+                    } else {
+                        strict_bool = true;
+                    }
+                }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("with") => {
+                    if let Str(lit) = m.lit {
+                        if with.is_some() {
+                            panic!("Duplicate `with` attribute.");
+                        } else if bool_format.is_some() {
+                            panic!("`with` attribute and `bool` attribute is disjoint.");
+                        } else if !child_tags.is_empty() {
+                            panic!("`with` attribute and `child` attribute is disjoint.");
+                        } else {
+                            with = Some(
+                                lit.parse::<syn::Path>()
+                                    .unwrap_or_else(|_| panic!("Expected a path.")),
+                            );
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
                     }
                 }
                 NestedMeta::Meta(NameValue(m)) if m.path.is_ident("attr") => {
@@ -269,6 +872,8 @@ impl Field {
                 NestedMeta::Meta(Path(ref p)) if p.is_ident("text") => {
                     if is_text {
                         panic!("Duplicate `text` attribute.");
+                    } else if ns.is_some() {
+                        panic!("`text` attribute and `ns` attribute is disjoint.");
                     } else if attr_tag.is_some() {
                         panic!("`text` attribute and `attr` attribute is disjoint.");
                     } else if !child_tags.is_empty() {
@@ -298,6 +903,8 @@ impl Field {
                             panic!("`child` attribute and `attr` attribute is disjoint.");
                         } else if is_cdata {
                             panic!("`child` attribute and `cdata` attribute is disjoint.")
+                        } else if with.is_some() {
+                            panic!("`child` attribute and `with` attribute is disjoint.");
                         } else if flatten_text_tag.is_some() {
                             panic!("`child` attribute and `flatten_text` attribute is disjoint.");
                         } else {
@@ -318,7 +925,32 @@ impl Field {
                         } else if flatten_text_tag.is_some() {
                             panic!("Duplicate `flatten_text` attribute.");
                         } else {
-                            flatten_text_tag = Some(lit);
+                            flatten_text_tag = Some(Some(lit));
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                // map keyed by tags of children
+                NestedMeta::Meta(Path(p)) if p.is_ident("flatten_text") => {
+                    if is_text {
+                        panic!("`flatten_text` attribute and `text` attribute is disjoint.");
+                    } else if !child_tags.is_empty() {
+                        panic!("`flatten_text` attribute and `child` attribute is disjoint.");
+                    } else if attr_tag.is_some() {
+                        panic!("`flatten_text` attribute and `attr` attribute is disjoint.");
+                    } else if flatten_text_tag.is_some() {
+                        panic!("Duplicate `flatten_text` attribute.");
+                    } else {
+                        flatten_text_tag = Some(None);
+                    }
+                }
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("key") => {
+                    if let Str(lit) = m.lit {
+                        if key.is_some() {
+                            panic!("Duplicate `key` attribute.");
+                        } else {
+                            key = Some(lit);
                         }
                     } else {
                         panic!("Expected a string literal.");
@@ -328,40 +960,308 @@ impl Field {
             }
         }
 
-        if let Some(tag) = attr_tag {
-            Field::Attribute {
-                name,
-                bind,
-                ty: Type::parse(field.ty),
-                tag,
-                default,
+        if skip {
+            if skip_read || skip_write {
+                panic!("`skip` attribute and `skip_read` or `skip_write` attribute is disjoint.");
+            } else if attr_tag.is_some()
+                || !child_tags.is_empty()
+                || is_text
+                || flatten_text_tag.is_some()
+                || is_flatten
+                || is_any
+                || is_any_attr
+                || is_comment
+                || pi_target.is_some()
+            {
+                panic!("`skip` attribute can't be used with `attr`, `child`, `text`, `flatten_text`, `flatten`, `any`, `any_attr`, `comment` or `pi` attribute.");
             }
-        } else if !child_tags.is_empty() {
-            Field::Child {
+
+            return Field::Skip {
                 name,
                 bind,
-                ty: Type::parse(field.ty),
-                default,
-                tags: child_tags,
+                read: None,
+                write: None,
+            };
+        } else if skip_read && skip_write {
+            panic!(
+                "`skip_read` attribute and `skip_write` attribute is disjoint, use `skip` instead."
+            );
+        }
+
+        // attributes and children are unqualified unless `ns` is specified or
+        // prefixes are ignored, since children are read by their own types,
+        // while `flatten_text` elements inherit the namespace of their parent
+        let attr_ns = ns
+            .as_ref()
+            .or_else(|| element_ns.filter(|ns| ns.value() == "*"));
+        let flatten_text_ns = ns.as_ref().or(element_ns);
+
+        let field = if is_comment || pi_target.is_some() {
+            let attribute = if is_comment { "comment" } else { "pi" };
+
+            if attr_tag.is_some()
+                || !child_tags.is_empty()
+                || is_text
+                || is_cdata
+                || flatten_text_tag.is_some()
+                || is_mixed
+                || is_any
+                || is_any_attr
+                || is_flatten
+            {
+                panic!("`{}` attribute can't be used with `attr`, `child`, `text`, `cdata`, `flatten_text`, `mixed`, `any`, `any_attr` or `flatten` attribute.", attribute);
+            } else if ns.is_some() || with.is_some() || default.is_some() {
+                panic!(
+                    "`{}` attribute can't be used with `ns`, `with` or `default` attribute.",
+                    attribute
+                );
             }
-        } else if is_text {
-            Field::Text {
-                name,
-                bind,
-                ty: Type::parse(field.ty),
-                is_cdata,
+
+            let ty = Type::parse(field.ty);
+
+            if !ty.is_vec() && !ty.is_option() {
+                panic!(
+                    "`{}` attribute only supports Vec<T> and Option<T>.",
+                    attribute
+                );
             }
-        } else if let Some(tag) = flatten_text_tag {
-            Field::FlattenText {
-                name,
-                bind,
-                ty: Type::parse(field.ty),
-                default,
-                tag,
-                is_cdata,
+
+            match pi_target {
+                Some(target) => Field::ProcessingInstruction {
+                    name,
+                    bind,
+                    ty,
+                    target,
+                },
+                None => Field::Comment { name, bind, ty },
             }
+        } else if is_flatten {
+            if attr_tag.is_some()
+                || !child_tags.is_empty()
+                || is_text
+                || is_cdata
+                || flatten_text_tag.is_some()
+                || is_mixed
+                || is_any
+                || is_any_attr
+            {
+                panic!("`flatten` attribute can't be used with `attr`, `child`, `text`, `cdata`, `flatten_text`, `mixed`, `any` or `any_attr` attribute.");
+            } else if ns.is_some() || with.is_some() || default.is_some() {
+                panic!(
+                    "`flatten` attribute can't be used with `ns`, `with` or `default` attribute."
+                );
+            }
+
+            let mut ty = field.ty;
+            elide_type_lifetimes(&mut ty);
+
+            Field::Flatten { name, bind, ty }
+        } else if is_any {
+            if attr_tag.is_some()
+                || !child_tags.is_empty()
+                || is_text
+                || is_cdata
+                || flatten_text_tag.is_some()
+                || is_mixed
+                || is_any_attr
+            {
+                panic!("`any` attribute can't be used with `attr`, `child`, `text`, `cdata`, `flatten_text`, `mixed` or `any_attr` attribute.");
+            } else if ns.is_some() || with.is_some() || default.is_some() {
+                panic!("`any` attribute can't be used with `ns`, `with` or `default` attribute.");
+            }
+
+            let ty = Type::parse(field.ty);
+
+            if !ty.is_vec() {
+                panic!("`any` attribute only supports Vec<T>.");
+            }
+
+            Field::Any { name, bind, ty }
+        } else if is_any_attr {
+            if attr_tag.is_some()
+                || !child_tags.is_empty()
+                || is_text
+                || is_cdata
+                || flatten_text_tag.is_some()
+                || is_mixed
+            {
+                panic!("`any_attr` attribute can't be used with `attr`, `child`, `text`, `cdata`, `flatten_text` or `mixed` attribute.");
+            } else if ns.is_some() || with.is_some() || default.is_some() {
+                panic!(
+                    "`any_attr` attribute can't be used with `ns`, `with` or `default` attribute."
+                );
+            }
+
+            let ty = Type::parse(field.ty);
+
+            if !matches!(ty, Type::VecT(..) | Type::T(_) | Type::MapT(..)) {
+                panic!("`any_attr` attribute only supports Vec<(K, V)> and maps.");
+            }
+
+            Field::AnyAttr { name, bind, ty }
+        } else if is_mixed && child_tags.is_empty() {
+            panic!("`mixed` attribute should be used together with `child` attribute.");
         } else {
-            panic!("Field should have one of `attr`, `child`, `text` or `flatten_text` attribute.");
+            let ty = Type::parse(field.ty);
+
+            if ty.is_map() {
+                if !child_tags.is_empty() && key.is_none() {
+                    panic!("`child` attribute on a map field should be used together with `key` attribute.");
+                } else if child_tags.is_empty() && !matches!(flatten_text_tag, Some(None)) {
+                    panic!("Map fields are only supported with `child` and `key` attribute, `flatten_text` attribute without tag, or `any_attr` attribute.");
+                } else if child_tags.is_empty() && key.is_some() {
+                    panic!(
+                        "`key` attribute can only be used on map fields with `child` attribute."
+                    );
+                } else if with.is_some() {
+                    panic!("`with` attribute doesn't support maps.");
+                }
+            } else if key.is_some() {
+                panic!("`key` attribute can only be used on map fields with `child` attribute.");
+            } else if let Some(None) = flatten_text_tag {
+                panic!("`flatten_text` attribute without tag can only be used on map fields.");
+            }
+
+            if separator.is_some() && !(ty.is_vec() && (attr_tag.is_some() || is_text)) {
+                panic!("`separator` attribute can only be used on Vec fields with `attr` or `text` attribute.");
+            }
+
+            let bool_format = if bool_format.is_some() || strict_bool {
+                if !matches!(ty, Type::Bool | Type::OptionBool | Type::VecBool(_))
+                    || !(attr_tag.is_some() || is_text || flatten_text_tag.is_some())
+                {
+                    panic!("`bool` and `strict_bool` attribute can only be used on bool, Option<bool> and Vec<bool> fields with `attr`, `text` or `flatten_text` attribute.");
+                }
+
+                Some(BoolFormat::parse(bool_format.as_ref(), strict_bool))
+            } else {
+                None
+            };
+
+            if ty.is_option() || ty.is_vec() || ty.is_map() {
+                if let Some(DefaultValue::Fn(_)) | Some(DefaultValue::Value(_)) = default {
+                    panic!("`default = \"\"` and `default_value` attribute doesn't support Option, Vec and maps.");
+                }
+            }
+
+            if let Some(tag) = attr_tag {
+                Field::Attribute {
+                    name,
+                    bind,
+                    ty,
+                    tag: qualify(attr_ns, tag),
+                    default,
+                    with,
+                    bool_format,
+                    separator,
+                }
+            } else if !child_tags.is_empty() {
+                Field::Child {
+                    name,
+                    bind,
+                    ty,
+                    default,
+                    tags: child_tags
+                        .into_iter()
+                        .map(|tag| qualify(attr_ns, tag))
+                        .collect(),
+                    is_mixed,
+                    key,
+                }
+            } else if is_text {
+                Field::Text {
+                    name,
+                    bind,
+                    ty,
+                    is_cdata,
+                    with,
+                    bool_format,
+                    separator,
+                }
+            } else if let Some(tag) = flatten_text_tag {
+                Field::FlattenText {
+                    name,
+                    bind,
+                    ty,
+                    default,
+                    tag: tag.map(|tag| qualify(flatten_text_ns, tag)),
+                    is_cdata,
+                    with,
+                    bool_format,
+                }
+            } else {
+                panic!("Field should have one of `attr`, `child`, `text`, `flatten_text`, `flatten` or `skip` attribute.");
+            }
+        };
+
+        if skip_read {
+            Field::Skip {
+                name: field.name().clone(),
+                bind: field.bind().clone(),
+                read: None,
+                write: Some(Box::new(field)),
+            }
+        } else if skip_write {
+            Field::Skip {
+                name: field.name().clone(),
+                bind: field.bind().clone(),
+                read: Some(Box::new(field)),
+                write: None,
+            }
+        } else {
+            field
+        }
+    }
+
+    pub fn name(&self) -> &TokenStream {
+        match self {
+            Field::Attribute { name, .. }
+            | Field::Child { name, .. }
+            | Field::Text { name, .. }
+            | Field::FlattenText { name, .. }
+            | Field::Skip { name, .. }
+            | Field::Any { name, .. }
+            | Field::AnyAttr { name, .. }
+            | Field::Comment { name, .. }
+            | Field::ProcessingInstruction { name, .. }
+            | Field::Flatten { name, .. } => name,
+        }
+    }
+
+    pub fn bind(&self) -> &Ident {
+        match self {
+            Field::Attribute { bind, .. }
+            | Field::Child { bind, .. }
+            | Field::Text { bind, .. }
+            | Field::FlattenText { bind, .. }
+            | Field::Skip { bind, .. }
+            | Field::Any { bind, .. }
+            | Field::AnyAttr { bind, .. }
+            | Field::Comment { bind, .. }
+            | Field::ProcessingInstruction { bind, .. }
+            | Field::Flatten { bind, .. } => bind,
+        }
+    }
+
+    /// Returns the field which is used when reading,
+    /// it's `Field::Skip` if this field is skipped
+    pub fn for_read(&self) -> &Field {
+        match self {
+            Field::Skip {
+                read: Some(field), ..
+            } => field,
+            field => field,
+        }
+    }
+
+    /// Returns the field which is used when writing,
+    /// it's `Field::Skip` if this field is skipped
+    pub fn for_write(&self) -> &Field {
+        match self {
+            Field::Skip {
+                write: Some(field), ..
+            } => field,
+            field => field,
         }
     }
 }
@@ -370,17 +1270,65 @@ impl Type {
     pub fn is_option(&self) -> bool {
         matches!(
             self,
-            Type::OptionCowStr | Type::OptionT(_) | Type::OptionBool
+            Type::OptionCowStr | Type::OptionStr | Type::OptionT(_) | Type::OptionBool
         )
     }
 
     pub fn is_vec(&self) -> bool {
-        matches!(self, Type::VecCowStr | Type::VecT(_) | Type::VecBool)
+        self.collection().is_some()
+    }
+
+    pub fn collection(&self) -> Option<&Collection> {
+        match self {
+            Type::VecCowStr(collection)
+            | Type::VecStr(collection)
+            | Type::VecT(_, collection)
+            | Type::VecBool(collection) => Some(collection),
+            _ => None,
+        }
+    }
+
+    pub fn is_map(&self) -> bool {
+        matches!(self, Type::MapT(..))
+    }
+
+    /// Returns `true` if it can't be an element, so a newtype over it
+    /// is read from the text content
+    ///
+    /// Only `String`, `char` and the numeric types, possibly path-qualified
+    /// like `std::string::String`, are known to be text among `T: FromStr`,
+    /// since a derive macro can't tell which traits a type implements.
+    /// Newtypes over other `FromStr` types need an explicit `#[xml(text)]`
+    /// on the inner field.
+    pub fn is_text(&self) -> bool {
+        fn is_primitive(ty: &syn::Type) -> bool {
+            const PRIMITIVES: &[&str] = &[
+                "String", "char", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32",
+                "u64", "u128", "usize", "f32", "f64",
+            ];
+
+            match ty {
+                syn::Type::Path(ty) => PRIMITIVES.iter().any(|name| is_type_name(&ty.path, name)),
+                _ => false,
+            }
+        }
+
+        match self {
+            Type::T(ty) | Type::OptionT(ty) | Type::VecT(ty, _) => is_primitive(ty),
+            Type::MapT(..) => false,
+            _ => true,
+        }
     }
 
     fn parse(mut ty: syn::Type) -> Self {
-        fn is_vec(ty: &syn::Type) -> Option<&syn::Type> {
+        fn is_vec(ty: &syn::Type) -> Option<(&syn::Type, Collection)> {
             let path = match ty {
+                syn::Type::Array(array) => {
+                    return Some((
+                        &array.elem,
+                        Collection::Array(ty.clone(), array.len.clone()),
+                    ))
+                }
                 syn::Type::Path(ty) => &ty.path,
                 _ => return None,
             };
@@ -389,9 +1337,29 @@ impl Type {
                 PathArguments::AngleBracketed(bracketed) => &bracketed.args,
                 _ => return None,
             };
+            let arg = match args.first()? {
+                GenericArgument::Type(arg) => arg,
+                _ => return None,
+            };
+            // the second argument of HashSet is the hasher
             if seg.ident == "Vec" && args.len() == 1 {
-                match args[0] {
-                    GenericArgument::Type(ref arg) => Some(arg),
+                Some((arg, Collection::Vec))
+            } else if seg.ident == "VecDeque" && args.len() == 1 {
+                Some((arg, Collection::VecDeque))
+            } else if (seg.ident == "BTreeSet" && args.len() == 1)
+                || (seg.ident == "HashSet" && (args.len() == 1 || args.len() == 2))
+            {
+                Some((arg, Collection::Set(ty.clone())))
+            } else if cfg!(feature = "smallvec") && seg.ident == "SmallVec" && args.len() == 1 {
+                match arg {
+                    syn::Type::Array(array) => {
+                        Some((&array.elem, Collection::SmallVec(ty.clone())))
+                    }
+                    _ => None,
+                }
+            } else if seg.ident == "Option" && args.len() == 1 {
+                match is_vec(arg)? {
+                    (arg, Collection::Vec) => Some((arg, Collection::OptionVec)),
                     _ => None,
                 }
             } else {
@@ -419,6 +1387,31 @@ impl Type {
             }
         }
 
+        fn is_map(ty: &syn::Type) -> Option<(&syn::Type, &syn::Type)> {
+            let path = match ty {
+                syn::Type::Path(ty) => &ty.path,
+                _ => return None,
+            };
+            let seg = path.segments.last()?;
+            let args = match &seg.arguments {
+                PathArguments::AngleBracketed(bracketed) => &bracketed.args,
+                _ => return None,
+            };
+            // the third argument of HashMap and IndexMap is the hasher
+            if (seg.ident == "HashMap" || seg.ident == "BTreeMap" || seg.ident == "IndexMap")
+                && (args.len() == 2 || args.len() == 3)
+            {
+                match (&args[0], &args[1]) {
+                    (GenericArgument::Type(key), GenericArgument::Type(value)) => {
+                        Some((key, value))
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }
+
         fn is_cow_str(ty: &syn::Type) -> bool {
             let path = match ty {
                 syn::Type::Path(ty) => &ty.path,
@@ -434,7 +1427,7 @@ impl Type {
             };
             if seg.ident == "Cow" && args.len() == 2 {
                 match &args[1] {
-                    GenericArgument::Type(syn::Type::Path(ty)) => ty.path.is_ident("str"),
+                    GenericArgument::Type(syn::Type::Path(ty)) => is_type_name(&ty.path, "str"),
                     _ => false,
                 }
             } else {
@@ -442,30 +1435,49 @@ impl Type {
             }
         }
 
+        fn is_str(ty: &syn::Type) -> bool {
+            match ty {
+                syn::Type::Reference(ty) if ty.mutability.is_none() => {
+                    matches!(&*ty.elem, syn::Type::Path(elem) if is_type_name(&elem.path, "str"))
+                }
+                _ => false,
+            }
+        }
+
         fn is_bool(ty: &syn::Type) -> bool {
-            matches!(ty, syn::Type::Path(ty) if ty.path.is_ident("bool"))
+            matches!(ty, syn::Type::Path(ty) if is_type_name(&ty.path, "bool"))
         }
 
         elide_type_lifetimes(&mut ty);
 
-        if let Some(ty) = is_vec(&ty) {
-            if is_cow_str(&ty) {
-                Type::VecCowStr
-            } else if is_bool(&ty) {
-                Type::VecBool
+        if let Some((key, value)) = is_map(&ty) {
+            let key = Box::new(Type::parse(key.clone()));
+            let value = Box::new(Type::parse(value.clone()));
+            Type::MapT(ty, key, value)
+        } else if let Some((ty, collection)) = is_vec(&ty) {
+            if is_cow_str(ty) {
+                Type::VecCowStr(collection)
+            } else if is_str(ty) {
+                Type::VecStr(collection)
+            } else if is_bool(ty) {
+                Type::VecBool(collection)
             } else {
-                Type::VecT(ty.clone())
+                Type::VecT(ty.clone(), collection)
             }
         } else if let Some(ty) = is_option(&ty) {
-            if is_cow_str(&ty) {
+            if is_cow_str(ty) {
                 Type::OptionCowStr
-            } else if is_bool(&ty) {
+            } else if is_str(ty) {
+                Type::OptionStr
+            } else if is_bool(ty) {
                 Type::OptionBool
             } else {
                 Type::OptionT(ty.clone())
             }
         } else if is_cow_str(&ty) {
             Type::CowStr
+        } else if is_str(&ty) {
+            Type::Str
         } else if is_bool(&ty) {
             Type::Bool
         } else {
@@ -474,7 +1486,21 @@ impl Type {
     }
 }
 
-fn get_xml_meta(attr: Attribute) -> Option<Vec<NestedMeta>> {
+// compares the last segment of the path, so `std::string::String`
+// is known as `String` too
+fn is_type_name(path: &syn::Path, name: &str) -> bool {
+    matches!(path.segments.last(), Some(seg) if seg.ident == name && seg.arguments.is_empty())
+}
+
+// prepends the namespace to the tag, i.e. `{$ns}$tag`
+fn qualify(ns: Option<&LitStr>, tag: LitStr) -> LitStr {
+    match ns {
+        Some(ns) => LitStr::new(&format!("{{{}}}{}", ns.value(), tag.value()), tag.span()),
+        None => tag,
+    }
+}
+
+pub fn get_xml_meta(attr: Attribute) -> Option<Vec<NestedMeta>> {
     if attr.path.segments.len() == 1 && attr.path.segments[0].ident == "xml" {
         match attr.parse_meta() {
             Ok(Meta::List(meta)) => Some(meta.nested.iter().cloned().collect()),
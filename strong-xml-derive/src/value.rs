@@ -0,0 +1,145 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Lit::*, Meta::*, *};
+
+use crate::types::get_xml_meta;
+
+/// Variant of an enum deriving `XmlValue`
+enum Variant {
+    /// Unit variant
+    ///
+    /// ```ignore
+    /// enum Foo {
+    ///     #[xml(value = "$value")]
+    ///     $name,
+    /// }
+    /// ```
+    Unit { name: Ident, value: LitStr },
+    /// Newtype variant holding any other value
+    ///
+    /// ```ignore
+    /// enum Foo {
+    ///     $name(String),
+    /// }
+    /// ```
+    Other { name: Ident },
+}
+
+pub fn impl_value(input: DeriveInput) -> TokenStream {
+    let name = &input.ident;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let data = match input.data {
+        Data::Enum(data) => data,
+        _ => panic!("`XmlValue` can only be derived for enums."),
+    };
+
+    let variants = data
+        .variants
+        .into_iter()
+        .map(Variant::parse)
+        .collect::<Vec<_>>();
+
+    let mut others = variants.iter().filter_map(|variant| match variant {
+        Variant::Other { name } => Some(name),
+        _ => None,
+    });
+
+    let other = others.next();
+
+    if others.next().is_some() {
+        panic!("Only one variant can hold other values.");
+    }
+
+    let from_str_arms = variants.iter().filter_map(|variant| match variant {
+        Variant::Unit {
+            name: variant,
+            value,
+        } => Some(quote! { #value => Ok(#name::#variant), }),
+        Variant::Other { .. } => None,
+    });
+
+    let from_str_fallback = match other {
+        Some(variant) => quote! { _ => Ok(#name::#variant(__value.to_owned().into())), },
+        None => quote! {
+            _ => Err(strong_xml::XmlError::UnknownValue {
+                name: stringify!(#name).to_owned(),
+                value: __value.to_owned(),
+            }),
+        },
+    };
+
+    let display_arms = variants.iter().map(|variant| match variant {
+        Variant::Unit {
+            name: variant,
+            value,
+        } => quote! { #name::#variant => f.write_str(#value), },
+        Variant::Other { name: variant } => {
+            quote! { #name::#variant(__value) => std::fmt::Display::fmt(__value, f), }
+        }
+    });
+
+    quote! {
+        impl #impl_generics std::str::FromStr for #name #ty_generics
+            #where_clause
+        {
+            type Err = strong_xml::XmlError;
+
+            fn from_str(__value: &str) -> strong_xml::XmlResult<Self> {
+                match __value {
+                    #( #from_str_arms )*
+                    #from_str_fallback
+                }
+            }
+        }
+
+        impl #impl_generics std::fmt::Display for #name #ty_generics
+            #where_clause
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    #( #display_arms )*
+                }
+            }
+        }
+    }
+}
+
+impl Variant {
+    fn parse(variant: syn::Variant) -> Variant {
+        let mut value = None;
+
+        for meta in variant.attrs.into_iter().filter_map(get_xml_meta).flatten() {
+            match meta {
+                NestedMeta::Meta(NameValue(m)) if m.path.is_ident("value") => {
+                    if let Str(lit) = m.lit {
+                        if value.is_some() {
+                            panic!("Duplicate `value` attribute.");
+                        } else {
+                            value = Some(lit);
+                        }
+                    } else {
+                        panic!("Expected a string literal.");
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        match (variant.fields, value) {
+            (Fields::Unit, Some(value)) => Variant::Unit {
+                name: variant.ident,
+                value,
+            },
+            (Fields::Unit, None) => panic!("Missing `value` attribute."),
+            (Fields::Unnamed(fields), None) if fields.unnamed.len() == 1 => Variant::Other {
+                name: variant.ident,
+            },
+            (Fields::Unnamed(_), Some(_)) => {
+                panic!("`value` attribute can only be used on unit variants.")
+            }
+            _ => panic!("`XmlValue` only supports unit variants and newtype variants."),
+        }
+    }
+}
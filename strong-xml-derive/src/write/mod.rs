@@ -1,5 +1,6 @@
 mod named;
 mod newtype;
+mod text;
 
 use crate::types::{Element, Field, Fields};
 
@@ -14,22 +15,33 @@ pub fn impl_write(element: Element) -> TokenStream {
         } => {
             let branches = variants.iter().map(|variant| match variant {
                 Fields::Named { name, fields, .. } => {
-                    let bindings = fields.iter().map(|field| match field {
+                    let bindings = fields.iter().map(|field| match field.for_write() {
                         Field::Attribute { bind, name, .. }
                         | Field::Child { bind, name, .. }
                         | Field::Text { bind, name, .. }
-                        | Field::FlattenText { bind, name, .. } => quote!( #name: #bind ),
+                        | Field::FlattenText { bind, name, .. }
+                        | Field::Any { bind, name, .. }
+                        | Field::AnyAttr { bind, name, .. }
+                        | Field::Comment { bind, name, .. }
+                        | Field::ProcessingInstruction { bind, name, .. }
+                        | Field::Flatten { bind, name, .. } => quote!( #name: #bind ),
+                        Field::Skip { name, .. } => quote!( #name: _ ),
                     });
                     quote!( #ele_name::#name { #( #bindings ),* } )
                 }
-                Fields::Newtype { name, .. } => quote!( #ele_name::#name(__inner) ),
+                Fields::Newtype { name, .. } | Fields::Text { name, .. } => {
+                    quote!( #ele_name::#name(__inner) )
+                }
+                Fields::Flatten { .. } => unreachable!(),
             });
 
             let read = variants.iter().map(|variant| match variant {
-                Fields::Named { tag, name, fields } => {
-                    named::write(&tag, quote!( #ele_name::#name ), &fields)
-                }
-                Fields::Newtype { name, .. } => newtype::write(quote!( #ele_name::#name )),
+                Fields::Named {
+                    tag, name, fields, ..
+                } => named::write(tag, quote!( #ele_name::#name ), fields),
+                Fields::Newtype { name, ty, .. } => newtype::write(ty, quote!( #ele_name::#name )),
+                Fields::Text { name, ty } => text::write(ty, quote!( #ele_name::#name )),
+                Fields::Flatten { .. } => unreachable!(),
             });
 
             quote! {
@@ -43,12 +55,20 @@ pub fn impl_write(element: Element) -> TokenStream {
             name: ele_name,
             fields,
         } => match fields {
-            Fields::Named { tag, name, fields } => {
-                let bindings = fields.iter().map(|field| match field {
+            Fields::Named {
+                tag, name, fields, ..
+            } => {
+                let bindings = fields.iter().map(|field| match field.for_write() {
                     Field::Attribute { bind, name, .. }
                     | Field::Child { bind, name, .. }
                     | Field::Text { bind, name, .. }
-                    | Field::FlattenText { bind, name, .. } => quote!( #name: #bind ),
+                    | Field::FlattenText { bind, name, .. }
+                    | Field::Any { bind, name, .. }
+                    | Field::AnyAttr { bind, name, .. }
+                    | Field::Comment { bind, name, .. }
+                    | Field::ProcessingInstruction { bind, name, .. }
+                    | Field::Flatten { bind, name, .. } => quote!( #name: #bind ),
+                    Field::Skip { name, .. } => quote!( #name: _ ),
                 });
 
                 let read = named::write(&tag, quote!(#name), &fields);
@@ -59,8 +79,8 @@ pub fn impl_write(element: Element) -> TokenStream {
                     #read
                 }
             }
-            Fields::Newtype { name, .. } => {
-                let read = newtype::write(quote!(#name));
+            Fields::Newtype { name, ty, .. } => {
+                let read = newtype::write(&ty, quote!(#name));
 
                 quote! {
                     let __inner = &self.0;
@@ -68,6 +88,17 @@ pub fn impl_write(element: Element) -> TokenStream {
                     #read
                 }
             }
+            Fields::Text { .. } | Fields::Flatten { .. } => unreachable!(),
         },
     }
 }
+
+pub fn impl_write_flatten(element: Element) -> TokenStream {
+    match element {
+        Element::Struct {
+            fields: Fields::Flatten { name, fields, .. },
+            ..
+        } => named::write_flatten(quote!(#name), &fields),
+        _ => unreachable!(),
+    }
+}
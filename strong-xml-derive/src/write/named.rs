@@ -1,76 +1,103 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Ident, LitStr};
+use syn::{Ident, LitStr, Path};
 
-use crate::types::{Field, Type};
+use crate::types::{BoolFormat, Collection, Field, Type};
 
 pub fn write(tag: &LitStr, ele_name: TokenStream, fields: &[Field]) -> TokenStream {
-    let write_attributes = fields.iter().filter_map(|field| match field {
-        Field::Attribute { tag, bind, ty, .. } => Some(write_attrs(&tag, &bind, &ty, &ele_name)),
+    let fields = fields.iter().map(Field::for_write).collect::<Vec<_>>();
+
+    let write_attributes = fields
+        .iter()
+        .filter_map(|field| write_attr_field(field, &ele_name));
+
+    let write_any_attr = fields.iter().filter_map(|field| match field {
+        Field::AnyAttr { bind, ty, .. } => Some(write_any_attr(bind, ty, &ele_name)),
         _ => None,
     });
 
     let write_text = fields.iter().filter_map(|field| match field {
         Field::Text {
-            bind, ty, is_cdata, ..
-        } => Some(write_text(tag, bind, ty, &ele_name, *is_cdata)),
-        _ => None,
-    });
-
-    let write_flatten_text = fields.iter().filter_map(|field| match field {
-        Field::FlattenText {
-            tag,
+            name,
             bind,
             ty,
             is_cdata,
-            ..
-        } => Some(write_flatten_text(tag, bind, ty, &ele_name, *is_cdata)),
-        _ => None,
-    });
-
-    let write_child = fields.iter().filter_map(|field| match field {
-        Field::Child { bind, ty, .. } => Some(write_child(bind, ty, &ele_name)),
+            with,
+            bool_format,
+            separator,
+        } => Some(write_text(
+            name,
+            bind,
+            ty,
+            to_str(ty, with.as_ref(), bool_format.as_ref()),
+            separator.as_ref(),
+            &ele_name,
+            *is_cdata,
+        )),
         _ => None,
     });
 
-    let is_leaf_element = fields
+    // children are written in the order of fields declaration
+    let write_children = fields
         .iter()
-        .all(|field| matches!(field, Field::Attribute { .. }));
+        .filter_map(|field| write_child_field(field, &ele_name));
+
+    let is_leaf_element = fields.iter().all(|field| {
+        matches!(
+            field,
+            Field::Attribute { .. } | Field::AnyAttr { .. } | Field::Skip { .. }
+        )
+    });
 
     let is_text_element = fields
         .iter()
         .any(|field| matches!(field, Field::Text { .. }));
 
     let can_self_close = fields.iter().all(|field| match field {
-        Field::Child { ty, .. } | Field::FlattenText { ty, .. } => ty.is_vec() || ty.is_option(),
+        Field::Child { ty, .. } | Field::FlattenText { ty, .. } => {
+            ty.is_vec() || ty.is_option() || ty.is_map()
+        }
         _ => true,
     });
 
     let content_is_empty = fields.iter().filter_map(|field| match field {
-        Field::Child { ty, bind, .. } | Field::FlattenText { ty, bind, .. } => {
-            if ty.is_vec() {
-                Some(quote! { #bind.is_empty() })
+        Field::Child { ty, bind, .. }
+        | Field::FlattenText { ty, bind, .. }
+        | Field::Comment { ty, bind, .. }
+        | Field::ProcessingInstruction { ty, bind, .. } => {
+            if ty.is_vec() || ty.is_map() {
+                Some(is_empty(bind, ty))
             } else if ty.is_option() {
                 Some(quote! { #bind.is_none() })
             } else {
                 None
             }
         }
+        Field::Flatten { bind, .. } => {
+            Some(quote! { strong_xml::XmlWriteFlatten::is_content_empty(#bind) })
+        }
+        Field::Any { bind, ty, .. } => Some(is_empty(bind, ty)),
         _ => None,
     });
 
+    let mark_mixed_content = mark_mixed_content(&fields);
+
     let write_element_end = if is_leaf_element {
         quote! { writer.write_element_end_empty()?; }
     } else if is_text_element {
-        quote! { #( #write_text )* }
+        quote! {
+            writer.write_element_end_open()?;
+            #( #write_text )*
+            writer.write_element_end_close(#tag)?;
+        }
     } else {
         quote! {
             if #can_self_close #( && #content_is_empty )* {
                 writer.write_element_end_empty()?;
             } else {
                 writer.write_element_end_open()?;
-                #( #write_child )*
-                #( #write_flatten_text )*
+                #mark_mixed_content
+                #( #write_children )*
                 writer.write_element_end_close(#tag)?;
             }
         }
@@ -83,17 +110,301 @@ pub fn write(tag: &LitStr, ele_name: TokenStream, fields: &[Field]) -> TokenStre
 
         #( #write_attributes )*
 
+        #( #write_any_attr )*
+
         #write_element_end
 
         strong_xml::log_finish_writing!(#ele_name);
     }
 }
 
-fn write_attrs(tag: &LitStr, name: &Ident, ty: &Type, ele_name: &TokenStream) -> TokenStream {
-    let to_str = to_str(ty);
+/// Implements `XmlWriteFlatten` for a tagless struct
+pub fn write_flatten(ele_name: TokenStream, fields: &[Field]) -> TokenStream {
+    let fields = fields
+        .iter()
+        .map(Field::for_write)
+        .filter(|field| match field {
+            Field::Text { .. } => panic!("`text` attribute can't be used in a struct without tag."),
+            Field::Any { .. } => panic!("`any` attribute can't be used in a struct without tag."),
+            Field::AnyAttr { .. } => {
+                panic!("`any_attr` attribute can't be used in a struct without tag.")
+            }
+            Field::Comment { .. } => {
+                panic!("`comment` attribute can't be used in a struct without tag.")
+            }
+            Field::ProcessingInstruction { .. } => {
+                panic!("`pi` attribute can't be used in a struct without tag.")
+            }
+            Field::FlattenText { tag: None, .. } => {
+                panic!(
+                    "`flatten_text` attribute without tag can't be used in a struct without tag."
+                )
+            }
+            _ => true,
+        })
+        .collect::<Vec<_>>();
+
+    let attr_bindings = fields.iter().filter_map(|field| match field {
+        Field::Attribute { name, bind, .. } | Field::Flatten { name, bind, .. } => {
+            Some(quote!( #name: #bind ))
+        }
+        _ => None,
+    });
+
+    let child_bindings = fields
+        .iter()
+        .filter_map(|field| match field {
+            Field::Child { name, bind, .. }
+            | Field::FlattenText { name, bind, .. }
+            | Field::Flatten { name, bind, .. } => Some(quote!( #name: #bind )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let write_attributes = fields
+        .iter()
+        .filter_map(|field| write_attr_field(field, &ele_name));
+
+    let write_children = fields
+        .iter()
+        .filter_map(|field| write_child_field(field, &ele_name));
+
+    let mark_mixed_content = mark_mixed_content(&fields);
+
+    let content_is_empty = fields.iter().filter_map(|field| match field {
+        Field::Child { ty, bind, .. } | Field::FlattenText { ty, bind, .. } => {
+            if ty.is_vec() || ty.is_map() {
+                Some(is_empty(bind, ty))
+            } else if ty.is_option() {
+                Some(quote! { #bind.is_none() })
+            } else {
+                Some(quote! { false })
+            }
+        }
+        Field::Flatten { bind, .. } => {
+            Some(quote! { strong_xml::XmlWriteFlatten::is_content_empty(#bind) })
+        }
+        _ => None,
+    });
+
+    quote! {
+        fn write_attributes<W: std::io::Write>(
+            &self,
+            mut writer: &mut strong_xml::XmlWriter<W>
+        ) -> strong_xml::XmlResult<()> {
+            let #ele_name { #( #attr_bindings, )* .. } = self;
+
+            #( #write_attributes )*
+
+            Ok(())
+        }
+
+        fn write_children<W: std::io::Write>(
+            &self,
+            mut writer: &mut strong_xml::XmlWriter<W>
+        ) -> strong_xml::XmlResult<()> {
+            let #ele_name { #( #child_bindings, )* .. } = self;
+
+            #mark_mixed_content
+
+            #( #write_children )*
+
+            Ok(())
+        }
+
+        fn is_content_empty(&self) -> bool {
+            let #ele_name { #( #child_bindings, )* .. } = self;
+
+            true #( && #content_is_empty )*
+        }
+    }
+}
+
+fn write_attr_field(field: &Field, ele_name: &TokenStream) -> Option<TokenStream> {
+    match field {
+        Field::Attribute {
+            tag,
+            name,
+            bind,
+            ty,
+            with,
+            bool_format,
+            separator,
+            ..
+        } => Some(write_attrs(
+            tag,
+            name,
+            bind,
+            ty,
+            to_str(ty, with.as_ref(), bool_format.as_ref()),
+            separator.as_ref(),
+            ele_name,
+        )),
+        Field::Flatten { bind, .. } => Some(quote! {
+            strong_xml::log_start_writing_field!(#ele_name, #bind);
+
+            strong_xml::XmlWriteFlatten::write_attributes(#bind, &mut writer)?;
+
+            strong_xml::log_finish_writing_field!(#ele_name, #bind);
+        }),
+        _ => None,
+    }
+}
+
+// returns the statements writing the child elements of `field`
+fn write_child_field(field: &Field, ele_name: &TokenStream) -> Option<TokenStream> {
+    match field {
+        Field::Child { bind, ty, .. } => Some(write_child(bind, ty, ele_name)),
+        Field::FlattenText {
+            tag: None,
+            bind,
+            ty,
+            is_cdata,
+            ..
+        } => Some(write_flatten_text_map(bind, ty, ele_name, *is_cdata)),
+        Field::FlattenText {
+            tag: Some(tag),
+            bind,
+            ty,
+            is_cdata,
+            with,
+            bool_format,
+            ..
+        } => Some(write_flatten_text(
+            tag,
+            bind,
+            ty,
+            to_str(ty, with.as_ref(), bool_format.as_ref()),
+            ele_name,
+            *is_cdata,
+        )),
+        Field::Flatten { bind, .. } => Some(write_flatten_children(bind, ele_name)),
+        Field::Any { bind, ty, .. } => Some(write_any(bind, ty, ele_name)),
+        Field::Comment { bind, ty, .. } => Some(write_misc(bind, ty, None, ele_name)),
+        Field::ProcessingInstruction {
+            bind, ty, target, ..
+        } => Some(write_misc(bind, ty, Some(target), ele_name)),
+        _ => None,
+    }
+}
+
+// mixed content isn't indented by pretty printing
+fn mark_mixed_content(fields: &[&Field]) -> Option<TokenStream> {
+    let is_mixed = fields
+        .iter()
+        .any(|field| matches!(field, Field::Child { is_mixed: true, .. }));
+
+    if is_mixed {
+        Some(quote! { writer.mark_mixed_content(); })
+    } else {
+        None
+    }
+}
+
+fn write_flatten_children(name: &Ident, ele_name: &TokenStream) -> TokenStream {
+    quote! {
+        strong_xml::log_start_writing_field!(#ele_name, #name);
+
+        strong_xml::XmlWriteFlatten::write_children(#name, &mut writer)?;
+
+        strong_xml::log_finish_writing_field!(#ele_name, #name);
+    }
+}
+
+// writes comments, or processing instructions if `target` is given
+fn write_misc(
+    name: &Ident,
+    ty: &Type,
+    target: Option<&LitStr>,
+    ele_name: &TokenStream,
+) -> TokenStream {
+    let to_str = to_str(ty, None, None);
+
+    let write_value = match target {
+        Some(target) => quote! { writer.write_processing_instruction(#target, #to_str)?; },
+        None => quote! { writer.write_comment(#to_str)?; },
+    };
+
+    let write_misc = if ty.is_vec() {
+        let iter_values = iter_values(name, ty);
+
+        quote! {
+            for __value in #iter_values {
+                #write_value
+            }
+        }
+    } else {
+        quote! {
+            if let Some(__value) = #name {
+                #write_value
+            }
+        }
+    };
+
+    quote! {
+        strong_xml::log_start_writing_field!(#ele_name, #name);
+
+        #write_misc
+
+        strong_xml::log_finish_writing_field!(#ele_name, #name);
+    }
+}
+
+fn write_any_attr(name: &Ident, ty: &Type, ele_name: &TokenStream) -> TokenStream {
+    let iter_values = if ty.is_vec() {
+        iter_values(name, ty)
+    } else {
+        quote! { #name }
+    };
+
+    quote! {
+        strong_xml::log_start_writing_field!(#ele_name, #name);
 
+        for (__key, __value) in #iter_values {
+            writer.write_attribute(__key, __value)?;
+        }
+
+        strong_xml::log_finish_writing_field!(#ele_name, #name);
+    }
+}
+
+fn write_any(name: &Ident, ty: &Type, ele_name: &TokenStream) -> TokenStream {
+    let to_str = to_str(ty, None, None);
+    let iter_values = iter_values(name, ty);
+
+    quote! {
+        strong_xml::log_start_writing_field!(#ele_name, #name);
+
+        for __value in #iter_values {
+            writer.write_raw(#to_str)?;
+        }
+
+        strong_xml::log_finish_writing_field!(#ele_name, #name);
+    }
+}
+
+fn write_attrs(
+    tag: &LitStr,
+    field_name: &TokenStream,
+    name: &Ident,
+    ty: &Type,
+    to_str: TokenStream,
+    separator: Option<&LitStr>,
+    ele_name: &TokenStream,
+) -> TokenStream {
     if ty.is_vec() {
-        panic!("`attr` attribute doesn't support Vec.");
+        let join_list = join_list(name, ty, &to_str, separator, ele_name, field_name);
+        let is_empty = is_empty(name, ty);
+
+        quote! {
+            strong_xml::log_start_writing_field!(#ele_name, #name);
+
+            if !#is_empty {
+                writer.write_attribute(#tag, &#join_list)?;
+            }
+
+            strong_xml::log_finish_writing_field!(#ele_name, #name);
+        }
     } else if ty.is_option() {
         quote! {
             strong_xml::log_start_writing_field!(#ele_name, #name);
@@ -127,52 +438,97 @@ fn write_child(name: &Ident, ty: &Type, ele_name: &TokenStream) -> TokenStream {
 
             strong_xml::log_finish_writing_field!(#ele_name, #name);
         },
-        Type::VecT(_) => quote! {
-            strong_xml::log_start_writing_field!(#ele_name, #name);
+        Type::VecT(..) => {
+            let iter_values = iter_values(name, ty);
 
-            for ele in #name {
-                ele.to_writer(&mut writer)?;
+            quote! {
+                strong_xml::log_start_writing_field!(#ele_name, #name);
+
+                for ele in #iter_values {
+                    ele.to_writer(&mut writer)?;
+                }
+
+                strong_xml::log_finish_writing_field!(#ele_name, #name);
             }
+        }
+        Type::T(_) => quote! {
+            strong_xml::log_start_writing_field!(#ele_name, #name);
+
+            #name.to_writer(&mut writer)?;
 
             strong_xml::log_finish_writing_field!(#ele_name, #name);
         },
-        Type::T(_) => quote! {
+        // keys are written by the children themselves
+        Type::MapT(..) => quote! {
             strong_xml::log_start_writing_field!(#ele_name, #name);
 
-            &#name.to_writer(&mut writer)?;
+            for ele in #name.values() {
+                ele.to_writer(&mut writer)?;
+            }
 
             strong_xml::log_finish_writing_field!(#ele_name, #name);
         },
-        _ => panic!("`child` attribute only supports Vec<T>, Option<T> and T."),
+        _ => panic!("`child` attribute only supports Vec<T>, Option<T>, T and maps of T."),
     }
 }
 
 fn write_text(
-    tag: &LitStr,
+    field_name: &TokenStream,
     name: &Ident,
     ty: &Type,
+    to_str: TokenStream,
+    separator: Option<&LitStr>,
     ele_name: &TokenStream,
     is_cdata: bool,
 ) -> TokenStream {
-    let to_str = to_str(ty);
     let wrtie_fn = if is_cdata {
         quote!(write_cdata_text)
     } else {
         quote!(write_text)
     };
 
-    quote! {
-        writer.write_element_end_open()?;
+    let write_value = if ty.is_vec() {
+        let join_list = join_list(name, ty, &to_str, separator, ele_name, field_name);
 
-        strong_xml::log_start_writing_field!(#ele_name, #name);
+        quote! {
+            writer.#wrtie_fn(&#join_list)?;
+        }
+    } else {
+        quote! {
+            let __value = #name;
 
-        let __value = &#name;
+            writer.#wrtie_fn(#to_str)?;
+        }
+    };
+
+    quote! {
+        strong_xml::log_start_writing_field!(#ele_name, #name);
 
-        writer.#wrtie_fn(#to_str)?;
+        #write_value
 
         strong_xml::log_finish_writing_field!(#ele_name, #name);
+    }
+}
+
+fn write_flatten_text_map(
+    name: &Ident,
+    ty: &Type,
+    ele_name: &TokenStream,
+    is_cdata: bool,
+) -> TokenStream {
+    let to_str = match ty {
+        Type::MapT(_, _, value_ty) => to_str(value_ty, None, None),
+        _ => panic!("`flatten_text` attribute without tag only supports maps."),
+    };
+
+    quote! {
+        strong_xml::log_start_writing_field!(#ele_name, #name);
+
+        for (__key, __value) in #name {
+            writer.write_flatten_text(&__key.to_string(), #to_str, #is_cdata)?;
+        }
 
-        writer.write_element_end_close(#tag)?;
+        strong_xml::log_finish_writing_field!(#ele_name, #name);
     }
 }
 
@@ -180,16 +536,17 @@ fn write_flatten_text(
     tag: &LitStr,
     name: &Ident,
     ty: &Type,
+    to_str: TokenStream,
     ele_name: &TokenStream,
     is_cdata: bool,
 ) -> TokenStream {
-    let to_str = to_str(ty);
-
     if ty.is_vec() {
+        let iter_values = iter_values(name, ty);
+
         quote! {
             strong_xml::log_finish_writing_field!(#ele_name, #name);
 
-            for __value in #name {
+            for __value in #iter_values {
                 writer.write_flatten_text(#tag, #to_str, #is_cdata)?;
             }
 
@@ -209,7 +566,7 @@ fn write_flatten_text(
         quote! {
             strong_xml::log_finish_writing_field!(#ele_name, #name);
 
-            let __value = &#name;
+            let __value = #name;
             writer.write_flatten_text(#tag, #to_str, #is_cdata)?;
 
             strong_xml::log_finish_writing_field!(#ele_name, #name);
@@ -217,19 +574,99 @@ fn write_flatten_text(
     }
 }
 
-fn to_str(ty: &Type) -> TokenStream {
+// joins items of a list into a string, items which would be read
+// back as other values are rejected
+fn join_list(
+    name: &Ident,
+    ty: &Type,
+    to_str: &TokenStream,
+    separator: Option<&LitStr>,
+    ele_name: &TokenStream,
+    field_name: &TokenStream,
+) -> TokenStream {
+    let iter_values = iter_values(name, ty);
+    let (separator, split_by) = match separator {
+        Some(separator) => (quote! { #separator }, quote! { Some(#separator) }),
+        None => (quote! { " " }, quote! { None }),
+    };
+
+    quote! {{
+        let mut __list = String::new();
+
+        for (__index, __value) in #iter_values.enumerate() {
+            let __item: &str = #to_str;
+            if !strong_xml::utils::is_list_item(__item, #split_by) {
+                return Err(strong_xml::XmlError::InvalidListItem {
+                    name: stringify!(#ele_name).to_owned(),
+                    field: stringify!(#field_name).to_owned(),
+                    item: __item.to_owned(),
+                });
+            }
+            if __index > 0 {
+                __list.push_str(#separator);
+            }
+            __list.push_str(__item);
+        }
+
+        __list
+    }}
+}
+
+// iterates over values of the repeated field
+fn iter_values(name: &Ident, ty: &Type) -> TokenStream {
+    match ty.collection() {
+        Some(Collection::OptionVec) => quote! { #name.iter().flatten() },
+        _ => quote! { #name.iter() },
+    }
+}
+
+// returns `true` if the repeated field or map has no values
+fn is_empty(name: &Ident, ty: &Type) -> TokenStream {
+    match ty.collection() {
+        Some(Collection::OptionVec) => quote! { #name.iter().all(Vec::is_empty) },
+        _ => quote! { #name.is_empty() },
+    }
+}
+
+pub fn to_str(ty: &Type, with: Option<&Path>, bool_format: Option<&BoolFormat>) -> TokenStream {
+    if let Some(with) = with {
+        return quote! { &#with::to_xml(__value) };
+    }
+
+    if let Some(BoolFormat {
+        true_values,
+        false_values,
+    }) = bool_format
+    {
+        let (true_value, false_value) = (&true_values[0], &false_values[0]);
+
+        return quote! {
+            match __value {
+                true => #true_value,
+                false => #false_value
+            }
+        };
+    }
+
     match &ty {
-        Type::CowStr | Type::OptionCowStr | Type::VecCowStr => {
+        Type::CowStr
+        | Type::OptionCowStr
+        | Type::VecCowStr(_)
+        | Type::Str
+        | Type::OptionStr
+        | Type::VecStr(_) => {
             quote! { __value }
         }
-        Type::Bool | Type::OptionBool | Type::VecBool => quote! {
+        Type::Bool | Type::OptionBool | Type::VecBool(_) => quote! {
             match __value {
                 true => "true",
                 false => "false"
             }
         },
-        Type::T(_) | Type::OptionT(_) | Type::VecT(_) => {
+        Type::T(_) | Type::OptionT(_) | Type::VecT(..) => {
             quote! { &format!("{}", __value) }
         }
+        // entries of maps are converted one by one
+        Type::MapT(..) => unreachable!(),
     }
 }
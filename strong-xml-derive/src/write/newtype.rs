@@ -1,11 +1,27 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-pub fn write(name: TokenStream) -> TokenStream {
+use crate::types::Type;
+
+pub fn write(ty: &Type, name: TokenStream) -> TokenStream {
+    let write = match ty {
+        Type::VecT(..) => quote! {
+            for __ele in __inner {
+                __ele.to_writer(writer)?;
+            }
+        },
+        Type::OptionT(_) => quote! {
+            if let Some(__ele) = __inner {
+                __ele.to_writer(writer)?;
+            }
+        },
+        _ => quote! { __inner.to_writer(writer)?; },
+    };
+
     quote! {
         strong_xml::log_start_writing!(#name);
 
-        __inner.to_writer(writer)?;
+        #write
 
         strong_xml::log_finish_writing!(#name);
     }
@@ -0,0 +1,18 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::named::to_str;
+use crate::types::Type;
+
+pub fn write(ty: &Type, name: TokenStream) -> TokenStream {
+    let to_str = to_str(ty, None, None);
+
+    quote! {
+        strong_xml::log_start_writing!(#name);
+
+        let __value = __inner;
+        writer.write_text(#to_str)?;
+
+        strong_xml::log_finish_writing!(#name);
+    }
+}
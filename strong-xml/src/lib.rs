@@ -95,12 +95,212 @@
 //! );
 //! ```
 //!
+//! A newtype struct or variant over `Option<T>` or `Vec<T>` reads an optional
+//! element or a sequence of sibling elements with the given tags. In a `child`
+//! field, siblings separated by other elements are appended to the same `Vec`.
+//! A newtype over `Cow<str>`, `&str`, `bool`, `String` or a primitive type reads
+//! the text content of the element instead, like a `#[xml(text)]` field.
+//! Any other type is assumed to be an element, even if it implements `FromStr`,
+//! so mark the inner field with `#[xml(text)]` to read such a type from the text.
+//!
+//! ```rust
+//! # use strong_xml::{XmlRead, XmlWrite};
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "id")]
+//! struct Id(String);
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "addr")]
+//! struct Addr(#[xml(text)] std::net::Ipv4Addr);
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "item")]
+//! struct Items(Vec<Item>);
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "item")]
+//! struct Item {}
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "list")]
+//! struct List {
+//!     #[xml(child = "id")]
+//!     id: Id,
+//!     #[xml(child = "addr")]
+//!     addr: Addr,
+//!     #[xml(child = "item")]
+//!     items: Items,
+//! }
+//!
+//! assert_eq!(
+//!     List::from_str(r#"<list><item/><id>a</id><addr>127.0.0.1</addr><item/></list>"#).unwrap(),
+//!     List {
+//!         id: Id("a".into()),
+//!         addr: Addr([127, 0, 0, 1].into()),
+//!         items: Items(vec![Item {}, Item {}]),
+//!     }
+//! );
+//! ```
+//!
+//! ### `#[xml(ns = "")]`
+//!
+//! Specifies the namespace of a struct, an enum or an enum variant.
+//! Elements are then matched by their namespace and local name,
+//! regardless of which prefix the document binds to that namespace.
+//!
+//! Tags of `flatten_text` fields inherit the namespace of their parent,
+//! while tags of `child` fields and attributes are unqualified unless
+//! the field specifies its own `ns`.
+//!
+//! An unqualified tag without prefix matches elements of that local name in
+//! any namespace, so `child = "item"` reads both `<item>` and `<a:item>`,
+//! whether `a` or the default namespace is bound. Use `ns` on the field and
+//! on the child type to match a single namespace. Unqualified attributes and
+//! prefixed tags like `a:item` are compared verbatim.
+//!
+//! When writing, `xmlns` declarations are emitted on the outermost element
+//! that needs them, with generated prefixes (`ns0`, `ns1`, ...) unless
+//! `XmlWriter::register_prefix` chooses one.
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(ns = "http://www.w3.org/2005/Atom", tag = "feed")]
+//! struct Feed<'a> {
+//!     #[xml(flatten_text = "title")]
+//!     title: Cow<'a, str>,
+//!     #[xml(ns = "http://www.w3.org/XML/1998/namespace", attr = "lang")]
+//!     lang: Option<Cow<'a, str>>,
+//! }
+//!
+//! assert_eq!(
+//!     Feed::from_str(r#"<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en"><title>foo</title></feed>"#).unwrap(),
+//!     Feed { title: "foo".into(), lang: Some("en".into()) }
+//! );
+//!
+//! assert_eq!(
+//!     Feed::from_str(r#"<a:feed xmlns:a="http://www.w3.org/2005/Atom"><a:title>bar</a:title></a:feed>"#).unwrap(),
+//!     Feed { title: "bar".into(), lang: None }
+//! );
+//!
+//! assert_eq!(
+//!     (Feed { title: "foo".into(), lang: Some("en".into()) }).to_string().unwrap(),
+//!     r#"<ns0:feed xmlns:ns0="http://www.w3.org/2005/Atom" xml:lang="en"><ns0:title>foo</ns0:title></ns0:feed>"#
+//! );
+//!
+//! let mut writer = strong_xml::XmlWriter::new(Vec::new());
+//! writer.register_prefix("", "http://www.w3.org/2005/Atom");
+//! (Feed { title: "bar".into(), lang: None }).to_writer(&mut writer).unwrap();
+//!
+//! assert_eq!(
+//!     String::from_utf8(writer.into_inner()).unwrap(),
+//!     r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>bar</title></feed>"#
+//! );
+//! ```
+//!
+//! ### `#[xml(ignore_prefix)]`
+//!
+//! Compares the tags and attribute keys of a struct, an enum or an enum variant
+//! by their local name only, ignoring any namespace prefix. Use
+//! `XmlReader::set_ignore_prefix` to enable it for every type instead.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "Envelope", ignore_prefix)]
+//! struct Envelope {
+//!     #[xml(attr = "version")]
+//!     version: usize,
+//! }
+//!
+//! assert_eq!(
+//!     Envelope::from_str(r#"<soap:Envelope soap:version="1"/>"#).unwrap(),
+//!     Envelope { version: 1 }
+//! );
+//!
+//! assert_eq!(
+//!     Envelope::from_str(r#"<env:Envelope version="2"></env:Envelope>"#).unwrap(),
+//!     Envelope { version: 2 }
+//! );
+//!
+//! assert_eq!(
+//!     (Envelope { version: 1 }).to_string().unwrap(),
+//!     r#"<Envelope version="1"/>"#
+//! );
+//! ```
+//!
+//! ### `#[xml(deny_unknown)]`
+//!
+//! Rejects attributes and child elements of a struct, an enum or an enum
+//! variant that don't match any field, instead of skipping them. Fields marked
+//! `any` or `any_attr` still capture them. Namespace declarations (`xmlns` and
+//! `xmlns:prefix`) are always accepted. Use `XmlReader::set_deny_unknown`
+//! to enable it for every type instead.
+//!
+//! ```rust
+//! use strong_xml::{XmlError, XmlRead};
+//!
+//! #[derive(XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "server", deny_unknown)]
+//! struct Server {
+//!     #[xml(flatten_text = "port")]
+//!     port: u16,
+//! }
+//!
+//! assert!(matches!(
+//!     Server::from_str(r#"<server><prot>80</prot></server>"#),
+//!     Err(XmlError::UnknownElement { line: 1, column: 10, .. })
+//! ));
+//!
+//! assert!(matches!(
+//!     Server::from_str(r#"<server verbose="true"><port>80</port></server>"#),
+//!     Err(XmlError::UnknownAttribute { line: 1, column: 9, .. })
+//! ));
+//! ```
+//!
+//! ### `#[xml(ordered)]`
+//!
+//! Requires child elements of a struct, an enum or an enum variant to appear
+//! in the order of fields declaration (`xs:sequence`), or returns
+//! `XmlError::UnorderedElement`. Children which aren't `Vec` can only appear
+//! once, or `XmlError::DuplicateElement` is returned.
+//!
+//! ```rust
+//! use strong_xml::{XmlError, XmlRead};
+//!
+//! #[derive(XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "person", ordered)]
+//! struct Person {
+//!     #[xml(flatten_text = "name")]
+//!     name: String,
+//!     #[xml(flatten_text = "email")]
+//!     emails: Vec<String>,
+//! }
+//!
+//! assert_eq!(
+//!     Person::from_str(r#"<person><name>a</name><email>b</email><email>c</email></person>"#).unwrap(),
+//!     Person { name: "a".into(), emails: vec!["b".into(), "c".into()] }
+//! );
+//!
+//! assert!(matches!(
+//!     Person::from_str(r#"<person><email>b</email><name>a</name></person>"#),
+//!     Err(XmlError::UnorderedElement { line: 1, column: 26, .. })
+//! ));
+//! ```
+//!
 //! ### `#[xml(attr = "")]`
 //!
 //! Specifies that a struct field is attribute. Support
-//! `Cow<str>`, `Option<Cow<str>>`, `T` and `Option<T>`
+//! `Cow<str>`, `&str`, `Option<Cow<str>>`, `Option<&str>`, `T` and `Option<T>`
 //! where `T: FromStr + Display`.
 //!
+//! Attribute values are unescaped, so reading a value containing entities
+//! into `&str` returns `XmlError::CannotBorrow`, which contains the value as
+//! written in the input.
+//!
 //! ```rust
 //! use strong_xml::{XmlRead, XmlWrite};
 //!
@@ -127,6 +327,9 @@
 //! Specifies that a struct field is a child element. Support
 //! `T`, `Option<T>`, `Vec<T>` where `T: XmlRead + XmlWrite`.
 //!
+//! `T` can also be `Box<T>`, `Rc<T>` or `Arc<T>`, e.g. `Option<Box<T>>` for
+//! recursive elements.
+//!
 //! ```rust
 //! use strong_xml::{XmlRead, XmlWrite};
 //!
@@ -174,8 +377,13 @@
 //!
 //! Specifies that a struct field is text content.
 //! Support `Cow<str>`, `Vec<Cow<str>>`, `Option<Cow<str>>`,
+//! `&str`, `Vec<&str>`, `Option<&str>`,
 //! `T`, `Vec<T>`, `Option<T>` where `T: FromStr + Display`.
 //!
+//! `&str` always borrows from the input, so text containing entities or
+//! split by comments can't be read into it and returns `XmlError::CannotBorrow`.
+//! Use `Cow<str>` if the text may need to be unescaped.
+//!
 //! ```rust
 //! use std::borrow::Cow;
 //! use strong_xml::{XmlRead, XmlWrite};
@@ -202,6 +410,7 @@
 //!
 //! Specifies that a struct field is child text element.
 //! Support `Cow<str>`, `Vec<Cow<str>>`, `Option<Cow<str>>`,
+//! `&str`, `Vec<&str>`, `Option<&str>`,
 //! `T`, `Vec<T>`, `Option<T>` where `T: FromStr + Display`.
 //!
 //! ```rust
@@ -226,6 +435,64 @@
 //! );
 //! ```
 //!
+//! ### `#[xml(key = "")]`
+//!
+//! Reads children into a `HashMap`, `BTreeMap` or `IndexMap`, keyed by the
+//! given attribute of each child. Should be used together with `child`.
+//! The attribute is written by the child itself, so it should be a field of it.
+//! The map keys aren't written at all: if a key doesn't match the attribute
+//! of its child, the attribute wins when reading it back.
+//!
+//! `flatten_text` without tag reads the other children into a map instead,
+//! keyed by their tags, like `flatten_text` fields created on the fly.
+//! Writing a key which isn't a valid XML name returns an error.
+//!
+//! Duplicate keys are reported as `XmlError::DuplicateKey`. Children are
+//! written in the iteration order of the map, which is arbitrary for
+//! `HashMap`, so use `BTreeMap` or `IndexMap` for a stable output.
+//!
+//! ```rust
+//! use std::collections::BTreeMap;
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "config")]
+//! struct Config {
+//!     #[xml(child = "entry", key = "key")]
+//!     entries: BTreeMap<String, Entry>,
+//!     #[xml(child = "properties")]
+//!     properties: Properties,
+//! }
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "entry")]
+//! struct Entry {
+//!     #[xml(attr = "key")]
+//!     key: String,
+//!     #[xml(text)]
+//!     value: usize,
+//! }
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "properties")]
+//! struct Properties {
+//!     #[xml(flatten_text)]
+//!     values: BTreeMap<String, String>,
+//! }
+//!
+//! let xml = r#"<config><entry key="a">1</entry><properties><foo>1</foo><bar>2</bar></properties></config>"#;
+//!
+//! let config = Config::from_str(xml).unwrap();
+//!
+//! assert_eq!(config.entries["a"].value, 1);
+//! assert_eq!(config.properties.values["bar"], "2");
+//!
+//! assert_eq!(
+//!     config.to_string().unwrap(),
+//!     r#"<config><entry key="a">1</entry><properties><bar>2</bar><foo>1</foo></properties></config>"#
+//! );
+//! ```
+//!
 //! ### `#[xml(cdata)]`
 //!
 //! Specifies a CDATA text. Should be used together with `text` or `flatten_text`.
@@ -267,6 +534,344 @@
 //! );
 //! ```
 //!
+//! ### `#[xml(separator = "")]`
+//!
+//! `Vec<T>` fields of `attr` or `text` attribute are read and written as a
+//! list of values (`xs:list`), which are separated by whitespace by default.
+//! Use `separator` to specify another separator.
+//!
+//! Writing an item which contains the separator, or whitespace by default,
+//! returns `XmlError::InvalidListItem`, since it would be read back as
+//! several items.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "polyline")]
+//! struct Polyline {
+//!     #[xml(attr = "class")]
+//!     class: Vec<String>,
+//!     #[xml(attr = "points", separator = ",")]
+//!     points: Vec<usize>,
+//! }
+//!
+//! assert_eq!(
+//!     (Polyline { class: vec!["a".into(), "b".into()], points: vec![1, 2, 3] }).to_string().unwrap(),
+//!     r#"<polyline class="a b" points="1,2,3"/>"#
+//! );
+//!
+//! assert_eq!(
+//!     Polyline::from_str(r#"<polyline class=" a  b " points="1, 2, 3"/>"#).unwrap(),
+//!     Polyline { class: vec!["a".into(), "b".into()], points: vec![1, 2, 3] }
+//! );
+//! ```
+//!
+//! ### `#[xml(mixed)]`
+//!
+//! Reads text nodes together with the child elements listed in `child`,
+//! in document order. Should be used on a `Vec<T>` field, where `T`
+//! is an enum with a `#[xml(text)]` variant for text nodes.
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "p")]
+//! struct Paragraph<'a> {
+//!     #[xml(mixed, child = "b")]
+//!     content: Vec<Inline<'a>>,
+//! }
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! enum Inline<'a> {
+//!     #[xml(text)]
+//!     Text(Cow<'a, str>),
+//!     #[xml(tag = "b")]
+//!     Bold(Bold<'a>),
+//! }
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "b")]
+//! struct Bold<'a> {
+//!     #[xml(text)]
+//!     text: Cow<'a, str>,
+//! }
+//!
+//! let paragraph = Paragraph {
+//!     content: vec![
+//!         Inline::Text("Hello ".into()),
+//!         Inline::Bold(Bold { text: "world".into() }),
+//!         Inline::Text("!".into()),
+//!     ],
+//! };
+//!
+//! assert_eq!(paragraph.to_string().unwrap(), r#"<p>Hello <b>world</b>!</p>"#);
+//!
+//! assert_eq!(Paragraph::from_str(r#"<p>Hello <b>world</b>!</p>"#).unwrap(), paragraph);
+//! ```
+//!
+//! ### `#[xml(flatten)]`
+//!
+//! Reads and writes the attributes and children of a struct without `tag`
+//! as a part of the current element. It's useful for sharing a group of
+//! fields between elements.
+//!
+//! Structs without `tag` can only be flattened into other elements,
+//! and can't contain a `text` field.
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! struct Common<'a> {
+//!     #[xml(attr = "id")]
+//!     id: Cow<'a, str>,
+//!     #[xml(flatten_text = "note")]
+//!     note: Option<Cow<'a, str>>,
+//! }
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "item")]
+//! struct Item<'a> {
+//!     #[xml(flatten)]
+//!     common: Common<'a>,
+//!     #[xml(attr = "name")]
+//!     name: Cow<'a, str>,
+//! }
+//!
+//! assert_eq!(
+//!     (Item {
+//!         common: Common { id: "1".into(), note: Some("note".into()) },
+//!         name: "item".into(),
+//!     }).to_string().unwrap(),
+//!     r#"<item id="1" name="item"><note>note</note></item>"#
+//! );
+//!
+//! assert_eq!(
+//!     Item::from_str(r#"<item name="item" id="1"/>"#).unwrap(),
+//!     Item {
+//!         common: Common { id: "1".into(), note: None },
+//!         name: "item".into(),
+//!     }
+//! );
+//! ```
+//!
+//! ### `#[xml(any)]`
+//!
+//! Collects child elements which aren't matched by other fields as raw xml,
+//! and writes them back unchanged. Should be used on a `Vec<T>` field.
+//!
+//! Namespaces declared by parent elements and used by a collected element
+//! are declared again on it, so it stays well-formed when written back.
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "root")]
+//! struct Root<'a> {
+//!     #[xml(flatten_text = "name")]
+//!     name: Cow<'a, str>,
+//!     #[xml(any)]
+//!     unknown: Vec<Cow<'a, str>>,
+//! }
+//!
+//! let root = Root::from_str(r#"<root><ext a="1"/><name>foo</name></root>"#).unwrap();
+//!
+//! assert_eq!(
+//!     root,
+//!     Root { name: "foo".into(), unknown: vec![r#"<ext a="1"/>"#.into()] }
+//! );
+//!
+//! assert_eq!(
+//!     root.to_string().unwrap(),
+//!     r#"<root><name>foo</name><ext a="1"/></root>"#
+//! );
+//! ```
+//!
+//! ### `#[xml(any_attr)]`
+//!
+//! Collects attributes which aren't matched by other fields, and writes
+//! them back after other attributes. Should be used on a `Vec<(K, V)>`,
+//! `BTreeMap<K, V>` or `HashMap<K, V>` field, where `K` and `V` can be
+//! converted from `&str` and `Cow<str>`, like `Cow<str>` and `String`.
+//!
+//! Namespace declarations aren't collected, since the writer declares the
+//! namespaces it needs. Keys with a declared prefix are collected as `{uri}local`
+//! and written with a prefix bound to `uri`.
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//! use std::collections::BTreeMap;
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "div")]
+//! struct Div<'a> {
+//!     #[xml(attr = "id")]
+//!     id: Cow<'a, str>,
+//!     #[xml(any_attr)]
+//!     attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+//! }
+//!
+//! let div = Div::from_str(r#"<div data-b="2" id="1" data-a="1"/>"#).unwrap();
+//!
+//! assert_eq!(div.attrs.len(), 2);
+//!
+//! assert_eq!(
+//!     div.to_string().unwrap(),
+//!     r#"<div id="1" data-a="1" data-b="2"/>"#
+//! );
+//! ```
+//!
+//! ### `#[xml(comment)]`
+//!
+//! Collects comments directly inside the element. Should be used on a
+//! `Vec<T>` field, or an `Option<T>` field which keeps the first comment.
+//! Comments are written at the position of the field among children.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "config")]
+//! struct Config {
+//!     #[xml(comment)]
+//!     comments: Vec<String>,
+//!     #[xml(flatten_text = "port")]
+//!     port: u16,
+//! }
+//!
+//! assert_eq!(
+//!     Config::from_str(r#"<config><port>80</port><!-- default port --></config>"#).unwrap(),
+//!     Config { comments: vec![" default port ".into()], port: 80 }
+//! );
+//!
+//! assert_eq!(
+//!     (Config { comments: vec![" default port ".into()], port: 80 }).to_string().unwrap(),
+//!     r#"<config><!-- default port --><port>80</port></config>"#
+//! );
+//! ```
+//!
+//! ### `#[xml(pi = "")]`
+//!
+//! Collects processing instructions with the specified target directly
+//! inside the element. Like `comment`, it should be used on a `Vec<T>`
+//! or an `Option<T>` field.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "document")]
+//! struct Document {
+//!     #[xml(pi = "mso-application")]
+//!     application: Option<String>,
+//!     #[xml(flatten_text = "body")]
+//!     body: String,
+//! }
+//!
+//! assert_eq!(
+//!     Document::from_str(r#"<document><?mso-application progid="Word.Document"?><body>text</body></document>"#).unwrap(),
+//!     Document { application: Some(r#"progid="Word.Document""#.into()), body: "text".into() }
+//! );
+//!
+//! assert_eq!(
+//!     (Document { application: None, body: "text".into() }).to_string().unwrap(),
+//!     r#"<document><body>text</body></document>"#
+//! );
+//! ```
+//!
+//! ### `#[xml(with = "")]`
+//!
+//! Converts the value with the functions of the given module instead of
+//! `FromStr` and `Display`. Should be used together with `attr`, `text` or `flatten_text`.
+//!
+//! The module should provide `from_xml(&str) -> Result<T, E>`,
+//! where `E: Into<Box<dyn Error + Send + Sync>>`, and `to_xml(&T) -> Cow<str>`.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! mod seconds {
+//!     use std::{borrow::Cow, num::ParseIntError, time::Duration};
+//!
+//!     pub fn from_xml(s: &str) -> Result<Duration, ParseIntError> {
+//!         s.parse().map(Duration::from_secs)
+//!     }
+//!
+//!     pub fn to_xml(duration: &Duration) -> Cow<'static, str> {
+//!         duration.as_secs().to_string().into()
+//!     }
+//! }
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "task")]
+//! struct Task {
+//!     #[xml(attr = "timeout", with = "seconds")]
+//!     timeout: std::time::Duration,
+//! }
+//!
+//! assert_eq!(
+//!     (Task { timeout: std::time::Duration::from_secs(30) }).to_string().unwrap(),
+//!     r#"<task timeout="30"/>"#
+//! );
+//!
+//! assert_eq!(
+//!     Task::from_str(r#"<task timeout="5"/>"#).unwrap(),
+//!     Task { timeout: std::time::Duration::from_secs(5) }
+//! );
+//! ```
+//!
+//! ### `#[xml(bool = "")]`
+//!
+//! Sets the strings of a `bool`, `Option<bool>` or `Vec<bool>` field. Should
+//! be used together with `attr`, `text` or `flatten_text`.
+//!
+//! | format             | `true`  | `false`  |
+//! |--------------------|---------|----------|
+//! | `true_false`       | `true`  | `false`  |
+//! | `numeric`          | `1`     | `0`      |
+//! | `yes_no`           | `yes`   | `no`     |
+//! | `strict_xsd`       | `true`  | `false`  |
+//! | `"Y\|N"` (custom)  | `Y`     | `N`      |
+//!
+//! By default, bools are written as `true` and `false`, and any of `t`, `true`,
+//! `y`, `yes`, `on`, `1` or `f`, `false`, `n`, `no`, `off`, `0` are accepted
+//! when reading, along with the strings of the format.
+//!
+//! With `strict_bool`, only the strings of the format are accepted. `strict_xsd`
+//! is always strict and accepts `true`, `false`, `1` and `0` like `xs:boolean`.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "option")]
+//! struct Opt {
+//!     #[xml(attr = "enabled", bool = "numeric")]
+//!     enabled: bool,
+//!     #[xml(attr = "visible", bool = "Y|N", strict_bool)]
+//!     visible: bool,
+//! }
+//!
+//! assert_eq!(
+//!     (Opt { enabled: true, visible: false }).to_string().unwrap(),
+//!     r#"<option enabled="1" visible="N"/>"#
+//! );
+//!
+//! assert_eq!(
+//!     Opt::from_str(r#"<option enabled="yes" visible="Y"/>"#).unwrap(),
+//!     Opt { enabled: true, visible: true }
+//! );
+//!
+//! assert!(Opt::from_str(r#"<option enabled="1" visible="yes"/>"#).is_err());
+//! ```
+//!
 //! ### `#[xml(default)]`
 //!
 //! Use `Default::default()` if the value is not present when reading.
@@ -293,6 +898,219 @@
 //! );
 //! ```
 //!
+//! `#[xml(default = "path")]` calls the given function instead, and
+//! `#[xml(default_value = "")]` parses the given value like the field itself
+//! (with `FromStr`, the `with` module, or `XmlRead` for `child` fields).
+//!
+//! ```rust
+//! use strong_xml::XmlRead;
+//!
+//! fn default_host() -> String {
+//!     "localhost".into()
+//! }
+//!
+//! #[derive(XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "server")]
+//! struct Server {
+//!     #[xml(default = "default_host", attr = "host")]
+//!     host: String,
+//!     #[xml(default_value = "8080", attr = "port")]
+//!     port: u16,
+//! }
+//!
+//! assert_eq!(
+//!     Server::from_str(r#"<server/>"#).unwrap(),
+//!     Server { host: "localhost".into(), port: 8080 }
+//! );
+//! ```
+//!
+//! ### `#[xml(skip)]`
+//!
+//! Skips a field when reading and writing. Its value is
+//! filled from `Default::default()` when reading.
+//!
+//! `#[xml(skip_read)]` and `#[xml(skip_write)]` only skip a field
+//! in one direction, and should be used together with `attr`, `child`,
+//! `text` or `flatten_text`.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "root")]
+//! struct Root {
+//!     #[xml(attr = "attr")]
+//!     attr: usize,
+//!     #[xml(skip)]
+//!     cache: Option<usize>,
+//!     #[xml(skip_read, attr = "version")]
+//!     version: usize,
+//! }
+//!
+//! assert_eq!(
+//!     (Root { attr: 1, cache: Some(2), version: 3 }).to_string().unwrap(),
+//!     r#"<root attr="1" version="3"/>"#
+//! );
+//!
+//! assert_eq!(
+//!     Root::from_str(r#"<root attr="1" version="3"/>"#).unwrap(),
+//!     Root { attr: 1, cache: None, version: 0 }
+//! );
+//! ```
+//!
+//! ### `#[xml(value = "")]`
+//!
+//! Used with `#[derive(XmlValue)]` on enums of unit variants, which
+//! implements `FromStr` and `Display` for them, so that they can be used
+//! in `attr`, `text` and `flatten_text` fields. A newtype variant without
+//! `value` attribute holds any other value.
+//!
+//! The catch-all variant is built with `String::into`, so its field must
+//! implement `From<String>`, like `String` or `Cow<str>`. It's written as is,
+//! so one holding the value of another variant, like `Color::Other("red".into())`,
+//! is read back as that variant.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlValue, XmlWrite};
+//!
+//! #[derive(XmlValue, PartialEq, Debug)]
+//! enum Color {
+//!     #[xml(value = "red")]
+//!     Red,
+//!     #[xml(value = "light-blue")]
+//!     LightBlue,
+//!     Other(String),
+//! }
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "shape")]
+//! struct Shape {
+//!     #[xml(attr = "color")]
+//!     color: Color,
+//!     #[xml(flatten_text = "fill")]
+//!     fill: Color,
+//! }
+//!
+//! assert_eq!(
+//!     (Shape { color: Color::LightBlue, fill: Color::Other("#fff".into()) }).to_string().unwrap(),
+//!     r##"<shape color="light-blue"><fill>#fff</fill></shape>"##
+//! );
+//!
+//! assert_eq!(
+//!     Shape::from_str(r#"<shape color="red"><fill>light-blue</fill></shape>"#).unwrap(),
+//!     Shape { color: Color::Red, fill: Color::LightBlue }
+//! );
+//!
+//! assert_eq!("red".parse::<Color>().unwrap(), Color::Red);
+//! assert_eq!(Color::Other("red".into()).to_string(), "red");
+//! ```
+//!
+//! ## Collections
+//!
+//! Wherever a `Vec<T>` is supported, the following collections can be used as well:
+//!
+//! - `VecDeque<T>`
+//! - `BTreeSet<T>` and `HashSet<T>`, duplicate values are reported as `XmlError::DuplicateValue`
+//! - `[T; N]`, a wrong number of values is reported as `XmlError::LengthMismatch`
+//! - `Option<Vec<T>>`, which is `None` if there's no values
+//! - `SmallVec<[T; N]>`, requires the `smallvec` feature
+//!
+//! ```rust
+//! use std::collections::BTreeSet;
+//! use strong_xml::{XmlError, XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "geometry")]
+//! struct Geometry {
+//!     #[xml(attr = "tags")]
+//!     tags: BTreeSet<String>,
+//!     #[xml(flatten_text = "coord")]
+//!     coords: [f64; 3],
+//!     #[xml(flatten_text = "note")]
+//!     notes: Option<Vec<String>>,
+//! }
+//!
+//! assert_eq!(
+//!     Geometry::from_str(r#"<geometry tags="b a"><coord>1</coord><coord>2</coord><coord>3</coord></geometry>"#).unwrap(),
+//!     Geometry {
+//!         tags: vec!["a".into(), "b".into()].into_iter().collect(),
+//!         coords: [1.0, 2.0, 3.0],
+//!         notes: None,
+//!     }
+//! );
+//!
+//! assert!(matches!(
+//!     Geometry::from_str(r#"<geometry><coord>1</coord></geometry>"#),
+//!     Err(XmlError::LengthMismatch { expected: 3, found: 1, .. })
+//! ));
+//!
+//! assert!(matches!(
+//!     Geometry::from_str(r#"<geometry tags="a a"/>"#),
+//!     Err(XmlError::DuplicateValue { .. })
+//! ));
+//! ```
+//!
+//! ## XML Document
+//!
+//! The XML declaration, DOCTYPE, comments and processing instructions around
+//! the root element are skipped when reading a type directly. Use
+//! `XmlDocument<T>` to keep them, or to write a declaration.
+//!
+//! ```rust
+//! use strong_xml::{XmlDocument, XmlRead, XmlWrite};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "root")]
+//! struct Root;
+//!
+//! let document = XmlDocument::<Root>::from_str(
+//!     r#"<?xml version="1.1"?><!DOCTYPE root><root/>"#
+//! ).unwrap();
+//!
+//! assert_eq!(document.declaration.unwrap().version, "1.1");
+//! assert_eq!(document.doctype.unwrap(), "<!DOCTYPE root>");
+//!
+//! assert_eq!(
+//!     XmlDocument::new(Root).to_string().unwrap(),
+//!     r#"<?xml version="1.0" encoding="UTF-8"?><root/>"#
+//! );
+//! ```
+//!
+//! ## Pretty Printing
+//!
+//! `to_string_pretty` puts every child element on its own line and indents
+//! it with two spaces. Elements containing text or mixed content are written
+//! as is. The indent string, line break and attribute wrapping can be
+//! configured on `XmlWriter` and passed to `to_writer`.
+//!
+//! ```rust
+//! use strong_xml::{XmlRead, XmlWrite, XmlWriter};
+//!
+//! #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+//! #[xml(tag = "list")]
+//! struct List {
+//!     #[xml(flatten_text = "item")]
+//!     items: Vec<String>,
+//! }
+//!
+//! let list = List { items: vec!["a".into(), "b".into()] };
+//!
+//! assert_eq!(
+//!     list.to_string_pretty().unwrap(),
+//!     "<list>\n  <item>a</item>\n  <item>b</item>\n</list>"
+//! );
+//!
+//! let mut writer = XmlWriter::new(Vec::new());
+//! writer.set_indent("\t");
+//! writer.set_newline("\r\n");
+//! list.to_writer(&mut writer).unwrap();
+//!
+//! assert_eq!(
+//!     String::from_utf8(writer.into_inner()).unwrap(),
+//!     "<list>\r\n\t<item>a</item>\r\n\t<item>b</item>\r\n</list>"
+//! );
+//! ```
+//!
 //! ## License
 //!
 //! MIT
@@ -308,25 +1126,30 @@ pub mod lib {
     pub use log;
 }
 
+mod xml_document;
 mod xml_error;
 mod xml_escape;
+mod xml_list;
+mod xml_name;
 mod xml_read;
 mod xml_reader;
 mod xml_unescape;
 mod xml_write;
 mod xml_writer;
 
+pub use self::xml_document::{XmlDeclaration, XmlDocument, XmlMisc};
 pub use self::xml_error::{XmlError, XmlResult};
-pub use self::xml_read::{XmlRead, XmlReadOwned};
+pub use self::xml_read::{XmlRead, XmlReadFlatten, XmlReadOwned, XmlReadText};
 pub use self::xml_reader::XmlReader;
-pub use self::xml_write::XmlWrite;
+pub use self::xml_write::{XmlWrite, XmlWriteFlatten};
 pub use self::xml_writer::XmlWriter;
 
-pub use strong_xml_derive::{XmlRead, XmlWrite};
+pub use strong_xml_derive::{XmlRead, XmlValue, XmlWrite};
 
 pub use xmlparser;
 
 pub mod utils {
     pub use super::xml_escape::xml_escape;
+    pub use super::xml_list::{is_list_item, split_list};
     pub use super::xml_unescape::xml_unescape;
 }
@@ -0,0 +1,224 @@
+use std::io::Write;
+
+use xmlparser::Token;
+
+use crate::{XmlError, XmlRead, XmlReader, XmlResult, XmlWrite, XmlWriter};
+
+/// An XML document, with the XML declaration, DOCTYPE, comments and
+/// processing instructions around its root element.
+///
+/// ```rust
+/// use strong_xml::{XmlDeclaration, XmlDocument, XmlMisc, XmlRead, XmlWrite};
+///
+/// #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+/// #[xml(tag = "note")]
+/// struct Note {
+///     #[xml(text)]
+///     text: String,
+/// }
+///
+/// let document = XmlDocument::<Note>::from_str(
+///     r#"<?xml version="1.0" standalone="yes"?><!DOCTYPE note><!-- a --><note>b</note>"#
+/// ).unwrap();
+///
+/// assert_eq!(
+///     document,
+///     XmlDocument {
+///         declaration: Some(XmlDeclaration {
+///             version: "1.0".into(),
+///             encoding: None,
+///             standalone: Some(true),
+///         }),
+///         before_doctype: vec![],
+///         doctype: Some("<!DOCTYPE note>".into()),
+///         prolog: vec![XmlMisc::Comment(" a ".into())],
+///         root: Note { text: "b".into() },
+///         epilog: vec![],
+///     }
+/// );
+///
+/// assert_eq!(
+///     XmlDocument::new(Note { text: "b".into() }).to_string().unwrap(),
+///     r#"<?xml version="1.0" encoding="UTF-8"?><note>b</note>"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlDocument<T> {
+    /// XML declaration, which is omitted when writing if it's `None`
+    pub declaration: Option<XmlDeclaration>,
+    /// Comments and processing instructions before the DOCTYPE, which are
+    /// always empty when reading a document without DOCTYPE
+    pub before_doctype: Vec<XmlMisc>,
+    /// Original text of the DOCTYPE, including `<!DOCTYPE` and `>`
+    pub doctype: Option<String>,
+    /// Comments and processing instructions after the DOCTYPE, or before the
+    /// root element if there's no DOCTYPE
+    pub prolog: Vec<XmlMisc>,
+    /// Root element
+    pub root: T,
+    /// Comments and processing instructions after the root element
+    pub epilog: Vec<XmlMisc>,
+}
+
+/// XML declaration, like `<?xml version="1.0" encoding="UTF-8"?>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlDeclaration {
+    pub version: String,
+    pub encoding: Option<String>,
+    pub standalone: Option<bool>,
+}
+
+/// Comment or processing instruction outside of the root element
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlMisc {
+    Comment(String),
+    ProcessingInstruction { target: String, content: String },
+}
+
+impl<T> XmlDocument<T> {
+    /// Creates a document with the declaration
+    /// `<?xml version="1.0" encoding="UTF-8"?>`.
+    pub fn new(root: T) -> Self {
+        XmlDocument {
+            declaration: Some(XmlDeclaration::default()),
+            before_doctype: Vec::new(),
+            doctype: None,
+            prolog: Vec::new(),
+            root,
+            epilog: Vec::new(),
+        }
+    }
+}
+
+impl Default for XmlDeclaration {
+    fn default() -> Self {
+        XmlDeclaration {
+            version: "1.0".to_owned(),
+            encoding: Some("UTF-8".to_owned()),
+            standalone: None,
+        }
+    }
+}
+
+impl XmlMisc {
+    fn from_token(token: Token) -> Option<XmlMisc> {
+        match token {
+            Token::Comment { text, .. } => Some(XmlMisc::Comment(text.as_str().to_owned())),
+            Token::ProcessingInstruction {
+                target, content, ..
+            } => Some(XmlMisc::ProcessingInstruction {
+                target: target.as_str().to_owned(),
+                content: content
+                    .map(|content| content.as_str().to_owned())
+                    .unwrap_or_default(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn to_writer<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()> {
+        match self {
+            XmlMisc::Comment(comment) => writer.write_comment(comment)?,
+            XmlMisc::ProcessingInstruction { target, content } => {
+                writer.write_processing_instruction(target, content)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, T: XmlRead<'a>> XmlRead<'a> for XmlDocument<T> {
+    fn from_reader(reader: &mut XmlReader<'a>) -> XmlResult<Self> {
+        let mut declaration = None;
+        let mut before_doctype = Vec::new();
+        let mut doctype = None;
+        let mut prolog = Vec::new();
+
+        while let Some(token) = reader.peek() {
+            match token {
+                Ok(Token::ElementStart { .. }) => break,
+                Ok(Token::EmptyDtd { .. }) | Ok(Token::DtdStart { .. }) => {
+                    doctype = Some(reader.read_doctype()?.to_owned());
+                    before_doctype = std::mem::take(&mut prolog);
+                    continue;
+                }
+                _ => (),
+            }
+
+            // we have call .peek() above, and it's safe to use unwrap
+            match reader.next().unwrap()? {
+                Token::Declaration {
+                    version,
+                    encoding,
+                    standalone,
+                    ..
+                } => {
+                    declaration = Some(XmlDeclaration {
+                        version: version.as_str().to_owned(),
+                        encoding: encoding.map(|encoding| encoding.as_str().to_owned()),
+                        standalone,
+                    });
+                }
+                Token::Text { .. } => (),
+                token => match XmlMisc::from_token(token) {
+                    Some(misc) => prolog.push(misc),
+                    None => {
+                        return Err(XmlError::UnexpectedToken {
+                            token: format!("{:?}", token),
+                        })
+                    }
+                },
+            }
+        }
+
+        let root = T::from_reader(reader)?;
+
+        let mut epilog = Vec::new();
+
+        while let Some(token) = reader.read_misc()? {
+            epilog.extend(XmlMisc::from_token(token));
+        }
+
+        Ok(XmlDocument {
+            declaration,
+            before_doctype,
+            doctype,
+            prolog,
+            root,
+            epilog,
+        })
+    }
+}
+
+impl<T: XmlWrite> XmlWrite for XmlDocument<T> {
+    fn to_writer<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()> {
+        if let Some(declaration) = &self.declaration {
+            writer.write_declaration(
+                &declaration.version,
+                declaration.encoding.as_deref(),
+                declaration.standalone,
+            )?;
+        }
+
+        for misc in &self.before_doctype {
+            misc.to_writer(writer)?;
+        }
+
+        if let Some(doctype) = &self.doctype {
+            writer.write_raw(doctype)?;
+        }
+
+        for misc in &self.prolog {
+            misc.to_writer(writer)?;
+        }
+
+        self.root.to_writer(writer)?;
+
+        for misc in &self.epilog {
+            misc.to_writer(writer)?;
+        }
+
+        Ok(())
+    }
+}
@@ -7,12 +7,79 @@ pub enum XmlError {
     Parser(ParserError),
     Utf8(Utf8Error),
     UnexpectedEof,
-    UnexpectedToken { token: String },
-    TagMismatch { expected: String, found: String },
-    MissingField { name: String, field: String },
-    UnterminatedEntity { entity: String },
-    UnrecognizedSymbol { symbol: String },
+    UnexpectedToken {
+        token: String,
+    },
+    TagMismatch {
+        expected: String,
+        found: String,
+    },
+    MissingField {
+        name: String,
+        field: String,
+    },
+    UnterminatedEntity {
+        entity: String,
+    },
+    UnrecognizedSymbol {
+        symbol: String,
+    },
     FromStr(Box<dyn Error + Send + Sync>),
+    UnknownValue {
+        name: String,
+        value: String,
+    },
+    UnknownAttribute {
+        element: String,
+        attribute: String,
+        line: usize,
+        column: usize,
+    },
+    UnknownElement {
+        element: String,
+        child: String,
+        line: usize,
+        column: usize,
+    },
+    UnorderedElement {
+        element: String,
+        child: String,
+        line: usize,
+        column: usize,
+    },
+    DuplicateElement {
+        element: String,
+        child: String,
+        line: usize,
+        column: usize,
+    },
+    DuplicateKey {
+        element: String,
+        child: String,
+        key: String,
+        line: usize,
+        column: usize,
+    },
+    DuplicateValue {
+        name: String,
+        field: String,
+    },
+    CannotBorrow {
+        name: String,
+        field: String,
+        value: String,
+    },
+    LengthMismatch {
+        name: String,
+        field: String,
+        expected: usize,
+        found: usize,
+    },
+    InvalidListItem {
+        name: String,
+        field: String,
+        item: String,
+    },
 }
 
 impl From<IOError> for XmlError {
@@ -75,6 +142,83 @@ impl std::fmt::Display for XmlError {
             UnterminatedEntity { entity } => write!(f, "unterminated XML entity: {}", entity),
             UnrecognizedSymbol { symbol } => write!(f, "unrecognized XML symbol: {}", symbol),
             FromStr(e) => write!(f, "error parsing XML value: {}", e),
+            UnknownValue { name, value } => {
+                write!(f, "unknown value of {:?}: {:?}", name, value)
+            }
+            UnknownAttribute {
+                element,
+                attribute,
+                line,
+                column,
+            } => write!(
+                f,
+                "unknown attribute {:?} in XML element {:?} at {}:{}",
+                attribute, element, line, column
+            ),
+            UnknownElement {
+                element,
+                child,
+                line,
+                column,
+            } => write!(
+                f,
+                "unknown child {:?} in XML element {:?} at {}:{}",
+                child, element, line, column
+            ),
+            UnorderedElement {
+                element,
+                child,
+                line,
+                column,
+            } => write!(
+                f,
+                "child {:?} out of order in XML element {:?} at {}:{}",
+                child, element, line, column
+            ),
+            DuplicateElement {
+                element,
+                child,
+                line,
+                column,
+            } => write!(
+                f,
+                "duplicate child {:?} in XML element {:?} at {}:{}",
+                child, element, line, column
+            ),
+            DuplicateKey {
+                element,
+                child,
+                key,
+                line,
+                column,
+            } => write!(
+                f,
+                "duplicate key {:?} of child {:?} in XML element {:?} at {}:{}",
+                key, child, element, line, column
+            ),
+            DuplicateValue { name, field } => {
+                write!(f, "duplicate value in XML of {:?}: {:?}", name, field)
+            }
+            CannotBorrow { name, field, value } => write!(
+                f,
+                "XML value of {:?}: {:?} containing entities or split by comments can't be borrowed as `&str`: {:?}",
+                name, field, value
+            ),
+            LengthMismatch {
+                name,
+                field,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {} values in XML of {:?}: {:?}, found {}",
+                expected, name, field, found
+            ),
+            InvalidListItem { name, field, item } => write!(
+                f,
+                "list item of {:?}: {:?} can't be written, since it contains the separator: {:?}",
+                name, field, item
+            ),
         }
     }
 }
@@ -0,0 +1,72 @@
+use std::borrow::Cow;
+
+fn is_xml_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\r' | '\n')
+}
+
+fn split<'a>(raw: &'a str, separator: Option<&str>) -> Vec<&'a str> {
+    match separator {
+        None => raw
+            .split(is_xml_whitespace)
+            .filter(|item| !item.is_empty())
+            .collect(),
+        Some(_) if raw.trim_matches(is_xml_whitespace).is_empty() => Vec::new(),
+        Some(separator) => raw
+            .split(separator)
+            .map(|item| item.trim_matches(is_xml_whitespace))
+            .collect(),
+    }
+}
+
+/// Splits a list value (`xs:list`) into its items.
+///
+/// Items are separated by XML whitespace if `separator` is `None`,
+/// otherwise by `separator` with surrounding whitespace trimmed.
+pub fn split_list<'a>(value: Cow<'a, str>, separator: Option<&str>) -> Vec<Cow<'a, str>> {
+    match value {
+        Cow::Borrowed(raw) => split(raw, separator)
+            .into_iter()
+            .map(Cow::Borrowed)
+            .collect(),
+        Cow::Owned(raw) => split(&raw, separator)
+            .into_iter()
+            .map(|item| Cow::Owned(item.to_owned()))
+            .collect(),
+    }
+}
+
+/// Returns `true` if `item` is read back as is after being written in
+/// a list value, i.e. it's not empty and doesn't contain XML whitespace if
+/// `separator` is `None`, otherwise it doesn't contain `separator` nor
+/// start or end with XML whitespace.
+pub fn is_list_item(item: &str, separator: Option<&str>) -> bool {
+    match separator {
+        None => !item.is_empty() && !item.contains(is_xml_whitespace),
+        Some(separator) => {
+            !item.contains(separator) && item.trim_matches(is_xml_whitespace) == item
+        }
+    }
+}
+
+#[test]
+fn test_is_list_item() {
+    assert!(is_list_item("a", None));
+    assert!(!is_list_item("a b", None));
+    assert!(!is_list_item("", None));
+    assert!(is_list_item("a b", Some(",")));
+    assert!(!is_list_item("a,b", Some(",")));
+    assert!(!is_list_item(" a", Some(",")));
+}
+
+#[test]
+fn test_split_list() {
+    assert_eq!(split_list("a b\t\n c ".into(), None), vec!["a", "b", "c"]);
+    assert_eq!(split_list("  ".into(), None), Vec::<Cow<str>>::new());
+    assert_eq!(split_list("1, 2,3".into(), Some(",")), vec!["1", "2", "3"]);
+    assert_eq!(split_list("a,,b".into(), Some(",")), vec!["a", "", "b"]);
+    assert_eq!(split_list(" ".into(), Some(",")), Vec::<Cow<str>>::new());
+    assert_eq!(
+        split_list(String::from("x y").into(), None),
+        vec![Cow::<str>::Owned("x".into()), Cow::Owned("y".into())]
+    );
+}
@@ -0,0 +1,85 @@
+/// Namespace which is implicitly bound to the `xml` prefix.
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Splits an expanded name written as `{uri}local` into its namespace
+/// and local part.
+///
+/// Returns `None` if the given name isn't an expanded name.
+pub fn split_expanded_name(name: &str) -> Option<(&str, &str)> {
+    if !name.starts_with('{') {
+        return None;
+    }
+
+    let end = name.find('}')?;
+
+    Some((&name[1..end], &name[end + 1..]))
+}
+
+/// Splits a prefixed name written as `prefix:local` into its prefix
+/// and local part. The prefix is empty if the given name isn't prefixed.
+pub fn split_prefixed_name(name: &str) -> (&str, &str) {
+    match name.find(':') {
+        Some(i) => (&name[0..i], &name[i + 1..]),
+        None => ("", name),
+    }
+}
+
+/// Returns the declared prefix if the given attribute is a namespace declaration,
+/// the empty prefix stands for the default namespace.
+///
+/// ```text
+/// xmlns="uri"   => Some("")
+/// xmlns:p="uri" => Some("p")
+/// ```
+pub fn namespace_declaration<'a>(prefix: &'a str, local: &'a str) -> Option<&'a str> {
+    if prefix == "xmlns" {
+        Some(local)
+    } else if prefix.is_empty() && local == "xmlns" {
+        Some("")
+    } else {
+        None
+    }
+}
+
+/// Returns whether the given string can be used as a tag, either a name like
+/// `foo` or `p:foo`, or an expanded name like `{uri}foo`.
+pub fn is_xml_name(name: &str) -> bool {
+    let name = match split_expanded_name(name) {
+        Some((_, local)) => local,
+        None => name,
+    };
+
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' || c == ':' => (),
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_alphanumeric() || "-._:\u{B7}".contains(c))
+}
+
+#[test]
+fn test_split_name() {
+    assert_eq!(split_expanded_name("{urn:x}foo"), Some(("urn:x", "foo")));
+    assert_eq!(split_expanded_name("{}foo"), Some(("", "foo")));
+    assert_eq!(split_expanded_name("foo"), None);
+    assert_eq!(split_prefixed_name("x:foo"), ("x", "foo"));
+    assert_eq!(split_prefixed_name("foo"), ("", "foo"));
+    assert_eq!(namespace_declaration("xmlns", "x"), Some("x"));
+    assert_eq!(namespace_declaration("", "xmlns"), Some(""));
+    assert_eq!(namespace_declaration("", "x"), None);
+}
+
+#[test]
+fn test_is_xml_name() {
+    assert!(is_xml_name("foo"));
+    assert!(is_xml_name("p:foo-1.2"));
+    assert!(is_xml_name("{urn:x}_foo"));
+    assert!(is_xml_name("日本"));
+    assert!(!is_xml_name(""));
+    assert!(!is_xml_name("1foo"));
+    assert!(!is_xml_name("a b"));
+    assert!(!is_xml_name("a/>"));
+    assert!(!is_xml_name("{urn:x}"));
+}
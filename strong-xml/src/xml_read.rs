@@ -1,8 +1,25 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
 use crate::{XmlReader, XmlResult};
 
 pub trait XmlRead<'a>: Sized {
     fn from_reader(reader: &mut XmlReader<'a>) -> XmlResult<Self>;
 
+    /// Reads an element into a value which was read from an earlier element
+    /// with the same tag, e.g. when a `child` field appears twice.
+    ///
+    /// The value is replaced by default, newtypes over `Vec<T>` append to it.
+    /// Types wrapping another `XmlRead` type, like smart pointers, should
+    /// override it and delegate to the wrapped value, so they're extended
+    /// the same way.
+    #[doc(hidden)]
+    fn extend_from_reader(&mut self, reader: &mut XmlReader<'a>) -> XmlResult<()> {
+        *self = Self::from_reader(reader)?;
+        Ok(())
+    }
+
     fn from_str(text: &'a str) -> XmlResult<Self> {
         let mut reader = XmlReader::new(text);
         Self::from_reader(&mut reader)
@@ -12,3 +29,88 @@ pub trait XmlRead<'a>: Sized {
 pub trait XmlReadOwned: for<'s> XmlRead<'s> {}
 
 impl<T> XmlReadOwned for T where T: for<'s> XmlRead<'s> {}
+
+// smart pointers are read as the element they point to,
+// which allows recursive and shared children
+
+impl<'a, T: XmlRead<'a>> XmlRead<'a> for Box<T> {
+    fn from_reader(reader: &mut XmlReader<'a>) -> XmlResult<Self> {
+        T::from_reader(reader).map(Box::new)
+    }
+
+    fn extend_from_reader(&mut self, reader: &mut XmlReader<'a>) -> XmlResult<()> {
+        T::extend_from_reader(self, reader)
+    }
+}
+
+impl<'a, T: XmlRead<'a>> XmlRead<'a> for Rc<T> {
+    fn from_reader(reader: &mut XmlReader<'a>) -> XmlResult<Self> {
+        T::from_reader(reader).map(Rc::new)
+    }
+
+    // a shared value can't be extended in place, so it's replaced instead
+    fn extend_from_reader(&mut self, reader: &mut XmlReader<'a>) -> XmlResult<()> {
+        match Rc::get_mut(self) {
+            Some(value) => T::extend_from_reader(value, reader),
+            None => {
+                *self = Self::from_reader(reader)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<'a, T: XmlRead<'a>> XmlRead<'a> for Arc<T> {
+    fn from_reader(reader: &mut XmlReader<'a>) -> XmlResult<Self> {
+        T::from_reader(reader).map(Arc::new)
+    }
+
+    fn extend_from_reader(&mut self, reader: &mut XmlReader<'a>) -> XmlResult<()> {
+        match Arc::get_mut(self) {
+            Some(value) => T::extend_from_reader(value, reader),
+            None => {
+                *self = Self::from_reader(reader)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reads a text node of mixed content.
+///
+/// It's implemented by `#[derive(XmlRead)]` on enums with a `#[xml(text)]` variant.
+#[doc(hidden)]
+pub trait XmlReadText<'a>: Sized {
+    fn from_text(text: Cow<'a, str>) -> XmlResult<Self>;
+}
+
+/// Reads the attributes and children of a tagless struct, which is flattened
+/// into another element with `#[xml(flatten)]`.
+///
+/// It's implemented by `#[derive(XmlRead)]` on named structs without `tag`.
+#[doc(hidden)]
+pub trait XmlReadFlatten<'a>: Sized {
+    /// Values read so far
+    type State;
+
+    fn init() -> Self::State;
+
+    fn is_attribute(reader: &XmlReader<'a>, key: &str) -> bool;
+
+    fn read_attribute(
+        state: &mut Self::State,
+        reader: &mut XmlReader<'a>,
+        key: &'a str,
+        value: Cow<'a, str>,
+    ) -> XmlResult<()>;
+
+    fn is_child(reader: &XmlReader<'a>, tag: &str) -> bool;
+
+    fn read_child(
+        state: &mut Self::State,
+        reader: &mut XmlReader<'a>,
+        tag: &'a str,
+    ) -> XmlResult<()>;
+
+    fn finish(state: Self::State) -> XmlResult<Self>;
+}
@@ -1,11 +1,16 @@
 use std::borrow::Cow;
+use std::cell::Cell;
 use std::iter::{Iterator, Peekable};
 
 use xmlparser::ElementEnd;
 use xmlparser::Error;
+use xmlparser::StrSpan;
 use xmlparser::Token;
 use xmlparser::Tokenizer;
 
+use crate::xml_name::{
+    namespace_declaration, split_expanded_name, split_prefixed_name, XML_NAMESPACE,
+};
 use crate::xml_unescape::xml_unescape;
 use crate::{XmlError, XmlResult};
 
@@ -13,21 +18,126 @@ use crate::{XmlError, XmlResult};
 ///
 /// It behaves almost exactly like `xmlparser::Tokenizer::from("...").peekable()`
 /// but with some helper functions.
+///
+/// It also keeps track of the currently open elements and the namespaces
+/// they declare, so element and attribute names can be matched by their
+/// namespace instead of their prefix. See [`XmlReader::is_element`].
 pub struct XmlReader<'a> {
+    text: &'a str,
     tokenizer: Peekable<Tokenizer<'a>>,
+    scopes: Vec<Scope<'a>>,
+    // scope of the element which is about to be read, once its tag is returned
+    pending: Option<Scope<'a>>,
+    // original text of the last attribute value or text which is read
+    raw_value: Cell<&'a str>,
+    ignore_prefix: bool,
+    deny_unknown: bool,
+}
+
+/// An open element and the namespaces declared on it.
+struct Scope<'a> {
+    tag: &'a str,
+    namespaces: Vec<(&'a str, &'a str)>,
 }
 
 impl<'a> XmlReader<'a> {
     #[inline]
     pub fn new(text: &'a str) -> XmlReader<'a> {
         XmlReader {
+            text,
             tokenizer: Tokenizer::from(text).peekable(),
+            scopes: Vec::new(),
+            pending: None,
+            raw_value: Cell::new(""),
+            ignore_prefix: false,
+            deny_unknown: false,
         }
     }
 
+    /// Compares element and attribute names by their local name only,
+    /// so `version` matches both `soap:version` and `env:version`.
+    ///
+    /// ```rust
+    /// use strong_xml::{XmlRead, XmlReader};
+    ///
+    /// #[derive(XmlRead, PartialEq, Debug)]
+    /// #[xml(tag = "Envelope")]
+    /// struct Envelope {
+    ///     #[xml(attr = "version")]
+    ///     version: usize,
+    /// }
+    ///
+    /// let mut reader = XmlReader::new(r#"<soap:Envelope soap:version="2"/>"#);
+    /// reader.set_ignore_prefix(true);
+    ///
+    /// assert_eq!(
+    ///     Envelope::from_reader(&mut reader).unwrap(),
+    ///     Envelope { version: 2 }
+    /// );
+    /// ```
+    #[inline]
+    pub fn set_ignore_prefix(&mut self, ignore_prefix: bool) {
+        self.ignore_prefix = ignore_prefix;
+    }
+
+    /// Returns an error when an element contains attributes or children
+    /// which aren't known by its type, instead of skipping them.
+    ///
+    /// It's the same as putting `#[xml(deny_unknown)]` on every element.
+    ///
+    /// ```rust
+    /// use strong_xml::{XmlError, XmlRead, XmlReader};
+    ///
+    /// #[derive(XmlRead, PartialEq, Debug)]
+    /// #[xml(tag = "server")]
+    /// struct Server {
+    ///     #[xml(flatten_text = "port")]
+    ///     port: Option<u16>,
+    /// }
+    ///
+    /// let mut reader = XmlReader::new("<server>\n  <prot>80</prot>\n</server>");
+    /// reader.set_deny_unknown(true);
+    ///
+    /// assert!(matches!(
+    ///     Server::from_reader(&mut reader),
+    ///     Err(XmlError::UnknownElement { line: 2, column: 4, .. })
+    /// ));
+    /// ```
+    #[inline]
+    pub fn set_deny_unknown(&mut self, deny_unknown: bool) {
+        self.deny_unknown = deny_unknown;
+    }
+
+    /// Returns `true` if unknown attributes and children should be rejected.
+    #[inline]
+    pub fn is_deny_unknown(&self) -> bool {
+        self.deny_unknown
+    }
+
     #[inline]
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<Result<Token<'a>, Error>> {
-        self.tokenizer.next()
+        let token = self.tokenizer.next();
+
+        match token {
+            Some(Ok(Token::ElementStart { span, .. })) => {
+                let scope = self.take_scope(&span.as_str()[1..]);
+                self.scopes.push(scope);
+            }
+            Some(Ok(Token::ElementEnd {
+                end: ElementEnd::Empty,
+                ..
+            }))
+            | Some(Ok(Token::ElementEnd {
+                end: ElementEnd::Close(_, _),
+                ..
+            })) => {
+                self.scopes.pop();
+            }
+            _ => (),
+        }
+
+        token
     }
 
     #[inline]
@@ -38,34 +148,44 @@ impl<'a> XmlReader<'a> {
     #[inline]
     pub fn read_text(&mut self, end_tag: &str) -> XmlResult<Cow<'a, str>> {
         let mut res = None;
+        let mut raw = None;
 
-        while let Some(token) = self.next() {
-            match token? {
+        while let Some(token) = self.tokenizer.peek() {
+            if let Ok(Token::ElementEnd {
+                end: ElementEnd::Close(_, _),
+                span,
+            }) = token
+            {
+                let span = span.as_str(); // </tag>
+                let tag = &span[2..span.len() - 1]; // remove `</` and `>`
+                if self.is_end_tag(tag, end_tag) {
+                    self.next();
+                    break;
+                } else {
+                    return Err(XmlError::TagMismatch {
+                        expected: end_tag.to_owned(),
+                        found: tag.to_owned(),
+                    });
+                }
+            }
+
+            // we have call .peek() above, and it's safe to use unwrap
+            match self.next().unwrap()? {
                 Token::ElementEnd {
                     end: ElementEnd::Open,
                     ..
                 }
                 | Token::Attribute { .. } => (),
                 Token::Text { text } => {
-                    res = Some(xml_unescape(text.as_str())?);
+                    raw = extend_span(raw, text);
+                    res = Some(concat_text(res, xml_unescape(text.as_str())?));
                 }
-                Token::Cdata { text, .. } => {
-                    res = Some(Cow::Borrowed(text.as_str()));
+                Token::Cdata { text, span } => {
+                    raw = extend_span(raw, span);
+                    res = Some(concat_text(res, Cow::Borrowed(text.as_str())));
                 }
-                Token::ElementEnd {
-                    end: ElementEnd::Close(_, _),
-                    span,
-                } => {
-                    let span = span.as_str(); // </tag>
-                    let tag = &span[2..span.len() - 1]; // remove `</` and `>`
-                    if end_tag == tag {
-                        break;
-                    } else {
-                        return Err(XmlError::TagMismatch {
-                            expected: end_tag.to_owned(),
-                            found: tag.to_owned(),
-                        });
-                    }
+                Token::Comment { span, .. } | Token::ProcessingInstruction { span, .. } => {
+                    raw = extend_span(raw, span);
                 }
                 token => {
                     return Err(XmlError::UnexpectedToken {
@@ -75,16 +195,110 @@ impl<'a> XmlReader<'a> {
             }
         }
 
+        self.raw_value
+            .set(raw.map(|(start, end)| &self.text[start..end]).unwrap_or(""));
+
         Ok(res.unwrap_or_default())
     }
 
+    /// Reads the next comment or processing instruction
+    /// before the next element start or end tag.
+    ///
+    /// Text in between is skipped, and it returns `None` if there's no
+    /// more comment or processing instruction before the next tag.
+    ///
+    /// ```rust
+    /// # use strong_xml::XmlReader;
+    /// use strong_xml::xmlparser::Token;
+    ///
+    /// let mut reader = XmlReader::new("<a><!-- x --> <?pi y?><b/></a>");
+    ///
+    /// reader.next(); // "<a"
+    /// reader.next(); // ">"
+    ///
+    /// assert!(matches!(
+    ///     reader.read_misc().unwrap(),
+    ///     Some(Token::Comment { text, .. }) if text.as_str() == " x "
+    /// ));
+    /// assert!(matches!(
+    ///     reader.read_misc().unwrap(),
+    ///     Some(Token::ProcessingInstruction { target, content: Some(content), .. })
+    ///         if target.as_str() == "pi" && content.as_str() == "y"
+    /// ));
+    /// assert!(reader.read_misc().unwrap().is_none());
+    /// assert_eq!(reader.find_element_start(None).unwrap(), Some("b"));
+    /// ```
+    #[inline]
+    pub fn read_misc(&mut self) -> XmlResult<Option<Token<'a>>> {
+        while let Some(token) = self.tokenizer.peek() {
+            if let Ok(Token::ElementStart { .. })
+            | Ok(Token::ElementEnd { .. })
+            | Ok(Token::Attribute { .. }) = token
+            {
+                break;
+            }
+
+            // we have call .peek() above, and it's safe to use unwrap
+            match self.next().unwrap()? {
+                token @ Token::Comment { .. } | token @ Token::ProcessingInstruction { .. } => {
+                    return Ok(Some(token));
+                }
+                _ => (),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Reads the text between child elements, used by mixed content.
+    ///
+    /// It stops before the next element start or end tag, and returns `None`
+    /// if there's no text in between. Comments and processing instructions
+    /// are skipped, and adjacent text and CDATA sections are concatenated.
+    ///
+    /// ```rust
+    /// # use strong_xml::XmlReader;
+    /// let mut reader = XmlReader::new("<p>Hello <b>world</b></p>");
+    ///
+    /// reader.next(); // "<p"
+    /// reader.next(); // ">"
+    ///
+    /// assert_eq!(reader.read_mixed_text().unwrap().unwrap(), "Hello ");
+    /// assert_eq!(reader.read_mixed_text().unwrap(), None);
+    /// assert_eq!(reader.find_element_start(None).unwrap(), Some("b"));
+    /// ```
+    #[inline]
+    pub fn read_mixed_text(&mut self) -> XmlResult<Option<Cow<'a, str>>> {
+        let mut res: Option<Cow<'a, str>> = None;
+
+        while let Some(token) = self.tokenizer.peek() {
+            if let Ok(Token::ElementStart { .. })
+            | Ok(Token::ElementEnd { .. })
+            | Ok(Token::Attribute { .. }) = token
+            {
+                break;
+            }
+
+            // we have call .peek() above, and it's safe to use unwrap
+            let text = match self.next().unwrap()? {
+                Token::Text { text } => xml_unescape(text.as_str())?,
+                Token::Cdata { text, .. } => Cow::Borrowed(text.as_str()),
+                _ => continue,
+            };
+
+            res = Some(concat_text(res, text));
+        }
+
+        Ok(res)
+    }
+
     #[inline]
     pub fn read_till_element_start(&mut self, end_tag: &str) -> XmlResult<()> {
         while let Some(token) = self.next() {
             match token? {
                 Token::ElementStart { span, .. } => {
                     let tag = &span.as_str()[1..];
-                    if end_tag == tag {
+                    if self.is_element(tag, end_tag) {
                         break;
                     } else {
                         self.read_to_end(tag)?;
@@ -104,6 +318,11 @@ impl<'a> XmlReader<'a> {
         Ok(())
     }
 
+    /// Reads the next attribute of the current element, and returns its key
+    /// and unescaped value. Returns `None` if there's no more attributes.
+    ///
+    /// Values used to be returned as written in the input, with entities
+    /// left escaped. An unknown entity is now an error.
     #[inline]
     pub fn find_attribute(&mut self) -> XmlResult<Option<(&'a str, Cow<'a, str>)>> {
         if let Some(token) = self.tokenizer.peek() {
@@ -112,7 +331,8 @@ impl<'a> XmlReader<'a> {
                     let value = value.as_str();
                     let span = span.as_str(); // key="value"
                     let key = &span[0..span.len() - value.len() - 3]; // remove `="`, value and `"`
-                    let value = Cow::Borrowed(value);
+                    self.raw_value.set(value);
+                    let value = xml_unescape(value)?;
                     self.next();
                     return Ok(Some((key, value)));
                 }
@@ -144,7 +364,9 @@ impl<'a> XmlReader<'a> {
         while let Some(token) = self.tokenizer.peek() {
             match token {
                 Ok(Token::ElementStart { span, .. }) => {
-                    return Ok(Some(&span.as_str()[1..]));
+                    let tag = &span.as_str()[1..];
+                    self.prepare_scope(tag);
+                    return Ok(Some(tag));
                 }
                 Ok(Token::ElementEnd {
                     end: ElementEnd::Close(_, _),
@@ -153,7 +375,7 @@ impl<'a> XmlReader<'a> {
                     let end_tag = end_tag.unwrap();
                     let span = span.as_str(); // </tag>
                     let tag = &span[2..span.len() - 1]; // remove `</` and `>`
-                    if tag == end_tag {
+                    if self.is_end_tag(tag, end_tag) {
                         self.next();
                         return Ok(None);
                     } else {
@@ -178,8 +400,572 @@ impl<'a> XmlReader<'a> {
         Err(XmlError::UnexpectedEof)
     }
 
+    /// Skips to the next sibling element and returns its tag, without reading it.
+    ///
+    /// Unlike [`XmlReader::find_element_start`], it returns `None` if there's
+    /// no more siblings, i.e. the parent element ends or the input ends.
+    /// It's used to read a sequence of sibling elements.
+    ///
+    /// ```rust
+    /// # use strong_xml::XmlReader;
+    /// let mut reader = XmlReader::new("<p><a/> <b/></p>");
+    ///
+    /// reader.next(); // "<p"
+    /// reader.next(); // ">"
+    ///
+    /// assert_eq!(reader.peek_element_start().unwrap(), Some("a"));
+    /// reader.next(); // "<a"
+    /// reader.next(); // "/>"
+    /// assert_eq!(reader.peek_element_start().unwrap(), Some("b"));
+    /// reader.next(); // "<b"
+    /// reader.next(); // "/>"
+    /// assert_eq!(reader.peek_element_start().unwrap(), None);
+    /// ```
+    #[inline]
+    pub fn peek_element_start(&mut self) -> XmlResult<Option<&'a str>> {
+        while let Some(token) = self.tokenizer.peek() {
+            match token {
+                Ok(Token::ElementStart { span, .. }) => {
+                    let tag = &span.as_str()[1..];
+                    self.prepare_scope(tag);
+                    return Ok(Some(tag));
+                }
+                Ok(Token::ElementEnd { .. }) | Ok(Token::Attribute { .. }) => return Ok(None),
+                _ => {
+                    // we have call .peek() above, and it's safe to use unwrap
+                    self.next().unwrap()?;
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns `true` if the element `tag` matches the given `name`.
+    ///
+    /// `tag` should be the name of the element which was just returned from
+    /// [`XmlReader::find_element_start`] or [`XmlReader::read_till_element_start`],
+    /// since namespace declarations of that element are taken into account.
+    ///
+    /// `name` can be either:
+    ///
+    /// - `local`, which matches an element named `local` in any namespace,
+    ///   whichever prefix it uses,
+    /// - `prefix:local`, which is compared with `tag` verbatim,
+    /// - `{uri}local`, which matches an element named `local` in namespace `uri`,
+    ///   regardless of the prefix it uses,
+    /// - `{}local`, which matches an element named `local` without namespace,
+    /// - `{*}local`, which matches an element named `local` in any namespace.
+    ///
+    /// If [`XmlReader::set_ignore_prefix`] is enabled, `prefix:local` is
+    /// compared by its local name only as well.
+    ///
+    /// ```rust
+    /// # use strong_xml::XmlReader;
+    /// let mut reader = XmlReader::new(r#"<a:foo xmlns:a="urn:x"/>"#);
+    ///
+    /// let tag = reader.find_element_start(None).unwrap().unwrap();
+    ///
+    /// assert!(reader.is_element(tag, "a:foo"));
+    /// assert!(reader.is_element(tag, "{urn:x}foo"));
+    /// assert!(reader.is_element(tag, "{*}foo"));
+    /// assert!(!reader.is_element(tag, "{urn:y}foo"));
+    /// assert!(!reader.is_element(tag, "{}foo"));
+    /// assert!(reader.is_element(tag, "foo"));
+    /// assert!(!reader.is_element(tag, "b:foo"));
+    /// ```
+    #[inline]
+    pub fn is_element(&self, tag: &str, name: &str) -> bool {
+        self.is_name(tag, name, true)
+    }
+
+    /// Returns `true` if the attribute `key` matches the given `name`.
+    ///
+    /// `key` should be the name of the attribute which was just returned from
+    /// [`XmlReader::find_attribute`]. See [`XmlReader::is_element`] for the
+    /// accepted forms of `name`. Note that unprefixed attributes never
+    /// belong to the default namespace.
+    #[inline]
+    pub fn is_attribute(&self, key: &str, name: &str) -> bool {
+        self.is_name(key, name, false)
+    }
+
+    fn is_name(&self, found: &str, name: &str, is_element: bool) -> bool {
+        if found == name {
+            return true;
+        }
+
+        let (prefix, found_local) = split_prefixed_name(found);
+
+        // namespace declarations are only matched verbatim
+        if !is_element && namespace_declaration(prefix, found_local).is_some() {
+            return false;
+        }
+
+        let (uri, local) = match split_expanded_name(name) {
+            Some(name) => name,
+            None if self.ignore_prefix => return found_local == split_prefixed_name(name).1,
+            // unprefixed element names match by their local name only
+            None if is_element && !name.contains(':') => ("*", name),
+            None => return false,
+        };
+
+        if found_local != local {
+            return false;
+        } else if uri == "*" {
+            return true;
+        }
+
+        if prefix.is_empty() && !is_element {
+            return uri.is_empty();
+        }
+
+        match self.lookup_namespace(prefix) {
+            Some(found_uri) => found_uri == uri,
+            None => prefix.is_empty() && uri.is_empty(),
+        }
+    }
+
+    /// Returns `true` if the attribute `key` is a namespace declaration,
+    /// i.e. `xmlns` or `xmlns:prefix`.
+    ///
+    /// ```
+    /// use strong_xml::XmlReader;
+    ///
+    /// let reader = XmlReader::new("");
+    ///
+    /// assert!(reader.is_namespace_declaration("xmlns"));
+    /// assert!(reader.is_namespace_declaration("xmlns:x"));
+    /// assert!(!reader.is_namespace_declaration("x:xmlns"));
+    /// ```
+    pub fn is_namespace_declaration(&self, key: &str) -> bool {
+        let (prefix, local) = split_prefixed_name(key);
+
+        namespace_declaration(prefix, local).is_some()
+    }
+
+    /// Returns the attribute `key` as an expanded name `{uri}local` if its
+    /// prefix is bound to a namespace, or `key` as is otherwise.
+    ///
+    /// ```
+    /// use strong_xml::XmlReader;
+    ///
+    /// let mut reader = XmlReader::new(r#"<a xmlns:x="urn:x" x:b="1" c="2" y:d="3"/>"#);
+    ///
+    /// reader.next(); // "<a"
+    ///
+    /// let mut keys = Vec::new();
+    /// while let Some((key, _)) = reader.find_attribute().unwrap() {
+    ///     keys.push(reader.expand_attribute(key));
+    /// }
+    ///
+    /// assert_eq!(keys, ["xmlns:x", "{urn:x}b", "c", "y:d"]);
+    /// ```
+    pub fn expand_attribute(&self, key: &'a str) -> Cow<'a, str> {
+        let (prefix, local) = split_prefixed_name(key);
+
+        if prefix.is_empty() || namespace_declaration(prefix, local).is_some() {
+            return Cow::Borrowed(key);
+        }
+
+        match self.lookup_namespace(prefix) {
+            Some(uri) => Cow::Owned(format!("{{{}}}{}", uri, local)),
+            None => Cow::Borrowed(key),
+        }
+    }
+
+    /// Creates an `UnknownAttribute` error for the attribute `key` of the
+    /// element being read, which was just returned from [`XmlReader::find_attribute`].
+    pub fn unknown_attribute(&self, key: &str) -> XmlError {
+        let (line, column) = self.position(key);
+
+        XmlError::UnknownAttribute {
+            element: self.current_tag().to_owned(),
+            attribute: key.to_owned(),
+            line,
+            column,
+        }
+    }
+
+    /// Creates an `UnknownElement` error for the child element `tag`,
+    /// which was just returned from [`XmlReader::find_element_start`].
+    pub fn unknown_element(&self, tag: &str) -> XmlError {
+        let (line, column) = self.position(tag);
+
+        XmlError::UnknownElement {
+            element: self.current_tag().to_owned(),
+            child: tag.to_owned(),
+            line,
+            column,
+        }
+    }
+
+    /// Creates an `UnorderedElement` error for the child element `tag`,
+    /// which was just returned from [`XmlReader::find_element_start`].
+    pub fn unordered_element(&self, tag: &str) -> XmlError {
+        let (line, column) = self.position(tag);
+
+        XmlError::UnorderedElement {
+            element: self.current_tag().to_owned(),
+            child: tag.to_owned(),
+            line,
+            column,
+        }
+    }
+
+    /// Creates a `DuplicateElement` error for the child element `tag`, which
+    /// was just returned from [`XmlReader::find_element_start`], and can only
+    /// appear once.
+    pub fn duplicate_element(&self, tag: &str) -> XmlError {
+        let (line, column) = self.position(tag);
+
+        XmlError::DuplicateElement {
+            element: self.current_tag().to_owned(),
+            child: tag.to_owned(),
+            line,
+            column,
+        }
+    }
+
+    /// Creates a `DuplicateKey` error for the child element `tag`, which was
+    /// just returned from [`XmlReader::find_element_start`], and whose `key`
+    /// is already read.
+    pub fn duplicate_key(&self, tag: &str, key: &str) -> XmlError {
+        let (line, column) = self.position(tag);
+
+        XmlError::DuplicateKey {
+            element: self.current_tag().to_owned(),
+            child: tag.to_owned(),
+            key: key.to_owned(),
+            line,
+            column,
+        }
+    }
+
+    /// Creates a `CannotBorrow` error for the field `field` of `name`, whose
+    /// value was just read and has to be unescaped or concatenated. The error
+    /// contains the value as written in the input.
+    ///
+    /// ```rust
+    /// # use strong_xml::{XmlError, XmlReader};
+    /// let mut reader = XmlReader::new("<a>b &amp; c</a>");
+    ///
+    /// reader.next(); // "<a"
+    /// reader.next(); // ">"
+    ///
+    /// assert_eq!(reader.read_text("a").unwrap(), "b & c");
+    /// assert!(matches!(
+    ///     reader.cannot_borrow("A", "text"),
+    ///     XmlError::CannotBorrow { value, .. } if value == "b &amp; c"
+    /// ));
+    /// ```
+    pub fn cannot_borrow(&self, name: &str, field: &str) -> XmlError {
+        XmlError::CannotBorrow {
+            name: name.to_owned(),
+            field: field.to_owned(),
+            value: self.raw_value.get().to_owned(),
+        }
+    }
+
+    fn current_tag(&self) -> &'a str {
+        self.scopes
+            .last()
+            .map(|scope| scope.tag)
+            .unwrap_or_default()
+    }
+
+    // returns the line and column (both start from 1) of the given slice of text
+    fn position(&self, name: &str) -> (usize, usize) {
+        let offset = (name.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|offset| *offset <= self.text.len())
+            .unwrap_or(0);
+
+        let text = &self.text[..offset];
+
+        let line = text.matches('\n').count() + 1;
+        let column = match text.rfind('\n') {
+            Some(i) => text[i + 1..].chars().count() + 1,
+            None => text.chars().count() + 1,
+        };
+
+        (line, column)
+    }
+
+    // closing tag must be the one of the innermost open element
+    fn is_end_tag(&self, found: &str, end_tag: &str) -> bool {
+        found == end_tag
+            || (matches!(self.scopes.last(), Some(scope) if scope.tag == found)
+                && self.is_element(found, end_tag))
+    }
+
+    // collects the namespaces declared by the element `tag`, whose start tag
+    // is the next token or has just been read, so they're known before its
+    // attributes are read and scanned only once per element
+    fn prepare_scope(&mut self, tag: &'a str) {
+        if matches!(&self.pending, Some(scope) if std::ptr::eq(scope.tag, tag)) {
+            return;
+        }
+
+        let mut tokenizer = self.tokenizer.clone();
+
+        if let Some(Ok(Token::ElementStart { .. })) = tokenizer.peek() {
+            tokenizer.next();
+        }
+
+        let mut namespaces = Vec::new();
+
+        while let Some(Ok(Token::Attribute {
+            prefix,
+            local,
+            value,
+            ..
+        })) = tokenizer.next()
+        {
+            if let Some(prefix) = namespace_declaration(prefix.as_str(), local.as_str()) {
+                namespaces.push((prefix, value.as_str()));
+            }
+        }
+
+        self.pending = Some(Scope { tag, namespaces });
+    }
+
+    fn take_scope(&mut self, tag: &'a str) -> Scope<'a> {
+        self.prepare_scope(tag);
+        self.pending.take().unwrap()
+    }
+
+    // finds the namespace bound to `prefix`, empty prefix stands for the default namespace
+    fn lookup_namespace(&self, prefix: &str) -> Option<&'a str> {
+        if prefix == "xml" {
+            return Some(XML_NAMESPACE);
+        }
+
+        // the element which is about to be read is innermost
+        self.pending
+            .iter()
+            .chain(self.scopes.iter().rev())
+            .flat_map(|scope| scope.namespaces.iter().rev())
+            .find(|(p, _)| *p == prefix)
+            .map(|(_, uri)| *uri)
+            .filter(|uri| !uri.is_empty())
+    }
+
+    /// Returns the value of the attribute `name` of the element which is about
+    /// to be read, without reading it. See [`XmlReader::is_element`] for the
+    /// accepted forms of `name`. The value is unescaped like the ones returned
+    /// from [`XmlReader::find_attribute`].
+    ///
+    /// It returns `XmlResult<Option<_>>` rather than `Option<_>`, since
+    /// unescaping the value can fail.
+    ///
+    /// ```rust
+    /// # use strong_xml::XmlReader;
+    /// let mut reader = XmlReader::new(r#"<root><entry key="a" value="1"/></root>"#);
+    ///
+    /// reader.next(); // "<root"
+    /// reader.next(); // ">"
+    ///
+    /// assert_eq!(reader.find_element_start(Some("root")).unwrap(), Some("entry"));
+    /// assert_eq!(reader.peek_attribute("key").unwrap().as_deref(), Some("a"));
+    /// assert_eq!(reader.peek_attribute("name").unwrap(), None);
+    /// assert_eq!(reader.read_raw_element().unwrap(), r#"<entry key="a" value="1"/>"#);
+    /// ```
+    pub fn peek_attribute(&self, name: &str) -> XmlResult<Option<Cow<'a, str>>> {
+        let mut tokenizer = self.tokenizer.clone();
+
+        if let Some(Ok(Token::ElementStart { .. })) = tokenizer.peek() {
+            tokenizer.next();
+        }
+
+        while let Some(Ok(Token::Attribute { span, value, .. })) = tokenizer.next() {
+            let value = value.as_str();
+            let span = span.as_str(); // key="value"
+            let key = &span[0..span.len() - value.len() - 3]; // remove `="`, value and `"`
+            if self.is_attribute(key, name) {
+                self.raw_value.set(value);
+                return xml_unescape(value).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Reads the element which is about to be read, and returns its
+    /// original text, including the start and end tag.
+    ///
+    /// Namespaces declared by its ancestors and used by it are declared again
+    /// on its start tag, so that the text can be written anywhere else. The
+    /// text is borrowed unless such declarations have to be added.
+    ///
+    /// ```rust
+    /// # use strong_xml::XmlReader;
+    /// let mut reader = XmlReader::new(r#"<root><ext a="1"><x/>text</ext></root>"#);
+    ///
+    /// reader.next(); // "<root"
+    /// reader.next(); // ">"
+    ///
+    /// assert_eq!(reader.find_element_start(Some("root")).unwrap(), Some("ext"));
+    /// assert_eq!(reader.read_raw_element().unwrap(), r#"<ext a="1"><x/>text</ext>"#);
+    /// assert_eq!(reader.find_element_start(Some("root")).unwrap(), None);
+    ///
+    /// let mut reader = XmlReader::new(r#"<a:root xmlns:a="urn:a" xmlns:v="urn:v"><v:ext/></a:root>"#);
+    ///
+    /// reader.next(); // "<a:root"
+    /// reader.next(); // "xmlns:a"
+    /// reader.next(); // "xmlns:v"
+    /// reader.next(); // ">"
+    ///
+    /// assert_eq!(reader.read_raw_element().unwrap(), r#"<v:ext xmlns:v="urn:v"/>"#);
+    /// ```
+    #[inline]
+    pub fn read_raw_element(&mut self) -> XmlResult<Cow<'a, str>> {
+        let (start, tag_end, prefix) = match self.next() {
+            Some(Ok(Token::ElementStart { span, prefix, .. })) => {
+                (span.start(), span.end(), prefix.as_str())
+            }
+            Some(Ok(token)) => {
+                return Err(XmlError::UnexpectedToken {
+                    token: format!("{:?}", token),
+                })
+            }
+            Some(Err(err)) => return Err(err.into()),
+            None => return Err(XmlError::UnexpectedEof),
+        };
+
+        let depth = self.scopes.len();
+
+        // prefixes used by the current start tag, and the ones of them which
+        // are not declared inside the element
+        let mut used = vec![prefix];
+        let mut undeclared = Vec::new();
+
+        let end = loop {
+            // the start tag has been read, including its namespace declarations
+            if let Some(Ok(Token::ElementEnd {
+                end: ElementEnd::Open,
+                ..
+            }))
+            | Some(Ok(Token::ElementEnd {
+                end: ElementEnd::Empty,
+                ..
+            })) = self.tokenizer.peek()
+            {
+                for prefix in used.drain(..) {
+                    let is_declared = self.scopes[depth - 1..]
+                        .iter()
+                        .any(|scope| scope.namespaces.iter().any(|(p, _)| *p == prefix));
+
+                    if !is_declared && !undeclared.contains(&prefix) {
+                        undeclared.push(prefix);
+                    }
+                }
+            }
+
+            match self.next() {
+                Some(token) => match token? {
+                    Token::ElementStart { prefix, .. } => used.push(prefix.as_str()),
+                    Token::Attribute { prefix, local, .. } => {
+                        let prefix = prefix.as_str();
+                        // unprefixed attributes have no namespace
+                        if !prefix.is_empty()
+                            && prefix != "xml"
+                            && namespace_declaration(prefix, local.as_str()).is_none()
+                        {
+                            used.push(prefix);
+                        }
+                    }
+                    Token::ElementEnd { span, .. } if self.scopes.len() < depth => {
+                        break span.end();
+                    }
+                    _ => (),
+                },
+                None => return Err(XmlError::UnexpectedEof),
+            }
+        };
+
+        let mut declarations = String::new();
+
+        for prefix in undeclared {
+            let uri = self.scopes[..depth - 1]
+                .iter()
+                .rev()
+                .flat_map(|scope| scope.namespaces.iter().rev())
+                .find(|(p, _)| *p == prefix)
+                .map(|(_, uri)| *uri);
+
+            // the default namespace doesn't need to be declared if it's empty
+            let uri = match uri {
+                Some(uri) if !uri.is_empty() || !prefix.is_empty() => uri,
+                _ => continue,
+            };
+
+            let quote = if uri.contains('"') { '\'' } else { '"' };
+
+            if prefix.is_empty() {
+                declarations.push_str(&format!(" xmlns={}{}{}", quote, uri, quote));
+            } else {
+                declarations.push_str(&format!(" xmlns:{}={}{}{}", prefix, quote, uri, quote));
+            }
+        }
+
+        self.raw_value.set(&self.text[start..end]);
+
+        if declarations.is_empty() {
+            Ok(Cow::Borrowed(&self.text[start..end]))
+        } else {
+            Ok(Cow::Owned(format!(
+                "{}{}{}",
+                &self.text[start..tag_end],
+                declarations,
+                &self.text[tag_end..end]
+            )))
+        }
+    }
+
+    /// Reads the DOCTYPE which is about to be read, and returns its original text.
+    ///
+    /// ```rust
+    /// # use strong_xml::XmlReader;
+    /// let mut reader = XmlReader::new(r#"<!DOCTYPE a [<!ENTITY b "c">]><a/>"#);
+    ///
+    /// assert_eq!(reader.read_doctype().unwrap(), r#"<!DOCTYPE a [<!ENTITY b "c">]>"#);
+    /// assert_eq!(reader.find_element_start(None).unwrap(), Some("a"));
+    /// ```
+    #[inline]
+    pub fn read_doctype(&mut self) -> XmlResult<&'a str> {
+        let start = match self.next() {
+            Some(Ok(Token::EmptyDtd { span, .. })) => return Ok(span.as_str()),
+            Some(Ok(Token::DtdStart { span, .. })) => span.start(),
+            Some(Ok(token)) => {
+                return Err(XmlError::UnexpectedToken {
+                    token: format!("{:?}", token),
+                })
+            }
+            Some(Err(err)) => return Err(err.into()),
+            None => return Err(XmlError::UnexpectedEof),
+        };
+
+        while let Some(token) = self.next() {
+            if let Token::DtdEnd { span } = token? {
+                return Ok(&self.text[start..span.end()]);
+            }
+        }
+
+        Err(XmlError::UnexpectedEof)
+    }
+
     #[inline]
     pub fn read_to_end(&mut self, end_tag: &str) -> XmlResult<()> {
+        if let Some(scope) = self.scopes.last() {
+            if !self.is_element(scope.tag, end_tag) {
+                return Err(XmlError::TagMismatch {
+                    expected: end_tag.to_owned(),
+                    found: scope.tag.to_owned(),
+                });
+            }
+        }
+
         while let Some(token) = self.next() {
             match token? {
                 // if this element is emtpy, just return
@@ -201,51 +987,13 @@ impl<'a> XmlReader<'a> {
             }
         }
 
-        let mut depth = 1;
+        // skip everything until this element is closed
+        let depth = self.scopes.len();
 
         while let Some(token) = self.next() {
-            match token? {
-                Token::ElementStart { span, .. } if end_tag == &span.as_str()[1..] => {
-                    while let Some(token) = self.next() {
-                        match token? {
-                            Token::ElementEnd {
-                                end: ElementEnd::Empty,
-                                ..
-                            } => {
-                                if depth == 0 {
-                                    return Ok(());
-                                } else {
-                                    // don't advance depth in this case
-                                    break;
-                                }
-                            }
-                            Token::ElementEnd {
-                                end: ElementEnd::Open,
-                                ..
-                            } => {
-                                depth += 1;
-                                break;
-                            }
-                            Token::Attribute { .. } => (),
-                            // there shouldn't have any token but Attribute between ElementStart and ElementEnd
-                            token => {
-                                return Err(XmlError::UnexpectedToken {
-                                    token: format!("{:?}", token),
-                                });
-                            }
-                        }
-                    }
-                }
-                Token::ElementEnd {
-                    end: ElementEnd::Close(_, _),
-                    span,
-                } if end_tag == &span.as_str()[2..span.as_str().len() - 1] => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return Ok(());
-                    }
-                }
-                _ => (),
+            token?;
+            if self.scopes.len() < depth {
+                return Ok(());
             }
         }
 
@@ -253,6 +1001,39 @@ impl<'a> XmlReader<'a> {
     }
 }
 
+// extends the range of the previously read text, if any, to the end of `span`
+fn extend_span(prev: Option<(usize, usize)>, span: StrSpan) -> Option<(usize, usize)> {
+    match prev {
+        Some((start, _)) => Some((start, span.end())),
+        None => Some((span.start(), span.end())),
+    }
+}
+
+// appends `text` to the previously read text, if any
+fn concat_text<'a>(prev: Option<Cow<'a, str>>, text: Cow<'a, str>) -> Cow<'a, str> {
+    match prev {
+        Some(prev) => Cow::Owned(prev.into_owned() + &text),
+        None => text,
+    }
+}
+
+#[test]
+fn read_mixed_text() -> XmlResult<()> {
+    let mut reader = XmlReader::new("<p>a &amp; <!-- comment --><![CDATA[<b>]]><b/>c</p>");
+
+    assert!(reader.next().is_some()); // "<p"
+    assert!(reader.next().is_some()); // ">"
+    assert_eq!(reader.read_mixed_text()?.as_deref(), Some("a & <b>"));
+    assert_eq!(reader.find_element_start(Some("p"))?, Some("b"));
+    assert!(reader.next().is_some()); // "<b"
+    reader.read_to_end("b")?;
+    assert_eq!(reader.read_mixed_text()?.as_deref(), Some("c"));
+    assert_eq!(reader.read_mixed_text()?, None);
+    assert_eq!(reader.find_element_start(Some("p"))?, None);
+
+    Ok(())
+}
+
 #[test]
 fn read_text() -> XmlResult<()> {
     let mut reader = XmlReader::new("<parent></parent>");
@@ -310,6 +1091,12 @@ fn read_text() -> XmlResult<()> {
     assert_eq!(reader.read_text("parent")?, "&quot;&apos;&lt;&gt;&amp;");
     assert!(reader.next().is_none());
 
+    reader = XmlReader::new("<parent>a<!-- comment --> &amp; <![CDATA[b]]></parent>");
+
+    assert!(reader.next().is_some()); // "<parent"
+    assert_eq!(reader.read_text("parent")?, "a & b");
+    assert!(reader.next().is_none());
+
     Ok(())
 }
 
@@ -360,6 +1147,46 @@ fn read_till_element_start() -> XmlResult<()> {
     Ok(())
 }
 
+#[test]
+fn is_element() -> XmlResult<()> {
+    let mut reader = XmlReader::new(
+        r#"<a:root xmlns:a="urn:a" xmlns="urn:b"><child a:attr=""/><a:child xmlns:a="urn:c"/><child xmlns=""/></a:root>"#,
+    );
+
+    let tag = reader.find_element_start(None)?.unwrap();
+    assert!(reader.is_element(tag, "{urn:a}root"));
+    reader.read_till_element_start(tag)?;
+    assert!(reader.is_element(tag, "{urn:a}root"));
+    assert!(reader.next().is_some()); // xmlns:a="urn:a"
+    assert!(reader.next().is_some()); // xmlns="urn:b"
+    assert!(reader.next().is_some()); // ">"
+
+    let tag = reader.find_element_start(Some("{urn:a}root"))?.unwrap();
+    assert!(reader.is_element(tag, "{urn:b}child"));
+    assert!(reader.next().is_some()); // "<child"
+    let (key, _) = reader.find_attribute()?.unwrap();
+    assert!(reader.is_attribute(key, "{urn:a}attr"));
+    assert!(!reader.is_attribute(key, "{urn:b}attr"));
+    assert!(reader.next().is_some()); // "/>"
+
+    let tag = reader.find_element_start(Some("{urn:a}root"))?.unwrap();
+    assert!(reader.is_element(tag, "{urn:c}child"));
+    assert!(!reader.is_element(tag, "{urn:a}child"));
+    reader.next(); // "<a:child"
+    reader.read_to_end(tag)?;
+
+    let tag = reader.find_element_start(Some("{urn:a}root"))?.unwrap();
+    assert!(reader.is_element(tag, "{}child"));
+    assert!(!reader.is_element(tag, "{urn:b}child"));
+    reader.next(); // "<child"
+    reader.read_to_end(tag)?;
+
+    assert!(reader.find_element_start(Some("{urn:a}root"))?.is_none());
+    assert!(reader.next().is_none());
+
+    Ok(())
+}
+
 #[test]
 fn read_to_end() -> XmlResult<()> {
     let mut reader = XmlReader::new("<parent><child/></parent>");
@@ -56,10 +56,10 @@ fn recognize(entity: &str, result: &mut String) -> XmlResult<()> {
         "lt" => result.push('<'),
         "amp" => result.push('&'),
         _ => {
-            let val = if entity.starts_with("#x") {
-                u32::from_str_radix(&entity[2..], 16).ok()
-            } else if entity.starts_with('#') {
-                u32::from_str_radix(&entity[1..], 10).ok()
+            let val = if let Some(hex) = entity.strip_prefix("#x") {
+                u32::from_str_radix(hex, 16).ok()
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse::<u32>().ok()
             } else {
                 None
             };
@@ -1,4 +1,6 @@
 use std::io::Write;
+use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::{XmlResult, XmlWriter};
 
@@ -12,4 +14,47 @@ pub trait XmlWrite {
 
         Ok(String::from_utf8(writer.inner)?)
     }
+
+    /// Like `to_string`, but indents element-only content with two spaces.
+    fn to_string_pretty(&self) -> XmlResult<String> {
+        let mut writer = XmlWriter::new(Vec::new());
+
+        writer.set_indent("  ");
+
+        self.to_writer(&mut writer)?;
+
+        Ok(String::from_utf8(writer.inner)?)
+    }
+}
+
+impl<T: XmlWrite> XmlWrite for Box<T> {
+    fn to_writer<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()> {
+        T::to_writer(self, writer)
+    }
+}
+
+impl<T: XmlWrite> XmlWrite for Rc<T> {
+    fn to_writer<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()> {
+        T::to_writer(self, writer)
+    }
+}
+
+impl<T: XmlWrite> XmlWrite for Arc<T> {
+    fn to_writer<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()> {
+        T::to_writer(self, writer)
+    }
+}
+
+/// Writes the attributes and children of a tagless struct, which is flattened
+/// into another element with `#[xml(flatten)]`.
+///
+/// It's implemented by `#[derive(XmlWrite)]` on named structs without `tag`.
+#[doc(hidden)]
+pub trait XmlWriteFlatten {
+    fn write_attributes<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()>;
+
+    fn write_children<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()>;
+
+    /// Returns `true` if `write_children` writes nothing
+    fn is_content_empty(&self) -> bool;
 }
@@ -1,34 +1,417 @@
+use std::borrow::Cow;
 use std::io::Result;
 use std::io::Write;
+use std::io::{Error, ErrorKind};
 
 use crate::xml_escape::xml_escape;
+use crate::xml_name::{
+    is_xml_name, namespace_declaration, split_expanded_name, split_prefixed_name, XML_NAMESPACE,
+};
 
+/// Xml Writer
+///
+/// Tags and attribute keys given as `{uri}local` are written with a prefix
+/// bound to `uri`, and the needed `xmlns` declarations are emitted on the
+/// outermost element which uses that namespace. Other names are written verbatim.
+///
+/// ```rust
+/// use strong_xml::XmlWriter;
+///
+/// let mut writer = XmlWriter::new(Vec::new());
+///
+/// writer.register_prefix("a", "urn:a");
+///
+/// writer.write_element_start("{urn:a}root").unwrap();
+/// writer.write_attribute("{urn:b}attr", "value").unwrap();
+/// writer.write_element_end_open().unwrap();
+/// writer.write_flatten_text("{urn:a}child", "text", false).unwrap();
+/// writer.write_element_end_close("{urn:a}root").unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(writer.into_inner()).unwrap(),
+///     r#"<a:root xmlns:a="urn:a" xmlns:ns0="urn:b" ns0:attr="value"><a:child>text</a:child></a:root>"#
+/// );
+/// ```
 pub struct XmlWriter<W: Write> {
     pub inner: W,
+    scopes: Vec<Scope>,
+    prefixes: Vec<(String, String)>,
+    generated_prefixes: usize,
+    indent: Option<String>,
+    newline: String,
+    attribute_per_line: bool,
+    is_started: bool,
+}
+
+/// An open element and the namespaces declared on it.
+struct Scope {
+    tag: Option<String>,
+    namespaces: Vec<(String, String)>,
+    // whether any element, comment or processing instruction is written in it
+    has_children: bool,
+    // whether it contains text, so its content isn't indented
+    is_mixed: bool,
+}
+
+impl Scope {
+    fn new(tag: Option<String>) -> Self {
+        Scope {
+            tag,
+            namespaces: Vec::new(),
+            has_children: false,
+            is_mixed: false,
+        }
+    }
 }
 
 impl<W: Write> XmlWriter<W> {
     pub fn new(inner: W) -> Self {
-        XmlWriter { inner }
+        XmlWriter {
+            inner,
+            scopes: Vec::new(),
+            prefixes: Vec::new(),
+            generated_prefixes: 0,
+            indent: None,
+            newline: "\n".to_owned(),
+            attribute_per_line: false,
+            is_started: false,
+        }
     }
 
     pub fn into_inner(self) -> W {
         self.inner
     }
 
+    /// Registers the preferred prefix of a namespace. An empty prefix means
+    /// the namespace should be declared as the default namespace.
+    ///
+    /// Namespaces without a preferred prefix are declared with generated
+    /// prefixes (`ns0`, `ns1`, ...), so they never take over the default
+    /// namespace of unqualified children.
+    ///
+    /// Elements with an unqualified name written inside a default namespace
+    /// undeclare it with `xmlns=""`, so they keep having no namespace.
+    pub fn register_prefix(&mut self, prefix: &str, uri: &str) {
+        self.prefixes.retain(|(_, u)| u != uri);
+        self.prefixes.push((prefix.to_owned(), uri.to_owned()));
+    }
+
+    /// Enables pretty printing, which puts every element, comment and
+    /// processing instruction on its own line, indented with `indent` for
+    /// each level of nesting.
+    ///
+    /// Only element-only content is indented. Once an element contains text,
+    /// its content is written as is, since adding whitespace to it would
+    /// change the text.
+    ///
+    /// ```rust
+    /// use strong_xml::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new());
+    ///
+    /// writer.set_indent("  ");
+    ///
+    /// writer.write_element_start("root").unwrap();
+    /// writer.write_element_end_open().unwrap();
+    /// writer.write_comment(" note ").unwrap();
+    /// writer.write_flatten_text("child", "text", false).unwrap();
+    /// writer.write_element_end_close("root").unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(writer.into_inner()).unwrap(),
+    ///     "<root>\n  <!-- note -->\n  <child>text</child>\n</root>"
+    /// );
+    /// ```
+    pub fn set_indent(&mut self, indent: &str) {
+        self.indent = Some(indent.to_owned());
+    }
+
+    /// Sets the line break used in pretty printing, `"\n"` by default.
+    pub fn set_newline(&mut self, newline: &str) {
+        self.newline = newline.to_owned();
+    }
+
+    /// Puts every attribute on its own line in pretty printing, indented one
+    /// level deeper than its element.
+    ///
+    /// ```rust
+    /// use strong_xml::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new());
+    ///
+    /// writer.set_indent("\t");
+    /// writer.set_newline("\r\n");
+    /// writer.set_attribute_per_line(true);
+    ///
+    /// writer.write_element_start("rect").unwrap();
+    /// writer.write_attribute("width", "10").unwrap();
+    /// writer.write_attribute("height", "20").unwrap();
+    /// writer.write_element_end_empty().unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(writer.into_inner()).unwrap(),
+    ///     "<rect\r\n\twidth=\"10\"\r\n\theight=\"20\"/>"
+    /// );
+    /// ```
+    pub fn set_attribute_per_line(&mut self, attribute_per_line: bool) {
+        self.attribute_per_line = attribute_per_line;
+    }
+
+    /// Marks the content of the current element as mixed, so it won't be
+    /// indented in pretty printing.
+    ///
+    /// Writing text marks it as well, but elements written before the
+    /// first text would have been indented already.
+    pub fn mark_mixed_content(&mut self) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.is_mixed = true;
+        }
+    }
+
     pub fn write_element_start(&mut self, tag: &str) -> Result<()> {
-        write!(self.inner, "<{}", tag)
+        self.write_indent()?;
+
+        let (uri, local) = match split_expanded_name(tag) {
+            Some(name) => name,
+            None => ("*", tag),
+        };
+
+        // `{*}local` matches any namespace when reading, and is written as is
+        if uri == "*" {
+            write!(self.inner, "<{}", local)?;
+            self.scopes.push(Scope::new(None));
+
+            // an unprefixed name written verbatim has no namespace
+            let is_unqualified = split_expanded_name(tag).is_none() && !tag.contains(':');
+            if is_unqualified && self.lookup_namespace("").is_some() {
+                self.declare_namespace(String::new(), String::new())?;
+            }
+
+            return Ok(());
+        }
+
+        let mut declaration = None;
+
+        let prefix = if uri.is_empty() {
+            // undeclare the default namespace if any
+            if self.lookup_namespace("").is_some() {
+                declaration = Some((String::new(), String::new()));
+            }
+            String::new()
+        } else if let Some(prefix) = self.lookup_prefix(uri, true) {
+            prefix
+        } else {
+            let prefix = match self.preferred_prefix(uri) {
+                Some(prefix) => prefix.to_owned(),
+                None => self.new_prefix(uri),
+            };
+            declaration = Some((prefix.clone(), uri.to_owned()));
+            prefix
+        };
+
+        let tag = if prefix.is_empty() {
+            local.to_owned()
+        } else {
+            format!("{}:{}", prefix, local)
+        };
+
+        write!(self.inner, "<{}", tag)?;
+
+        self.scopes.push(Scope::new(Some(tag)));
+
+        if let Some((prefix, uri)) = declaration {
+            self.declare_namespace(prefix, uri)?;
+        }
+
+        Ok(())
     }
 
+    /// Writes an attribute of the current element.
+    ///
+    /// A namespace declaration which the element already has is skipped,
+    /// and one binding its prefix to another namespace returns an error
+    /// of kind `InvalidInput`.
+    ///
+    /// ```rust
+    /// use strong_xml::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new());
+    ///
+    /// writer.write_element_start("{urn:a}e").unwrap();
+    /// writer.write_attribute("xmlns:ns0", "urn:a").unwrap();
+    /// assert!(writer.write_attribute("xmlns:ns0", "urn:b").is_err());
+    /// writer.write_element_end_empty().unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(writer.into_inner()).unwrap(),
+    ///     r#"<ns0:e xmlns:ns0="urn:a"/>"#
+    /// );
+    /// ```
     pub fn write_attribute(&mut self, key: &str, value: &str) -> Result<()> {
-        write!(self.inner, r#" {}="{}""#, key, xml_escape(value))
+        // namespace declarations are tracked, so they can be used by children
+        let (prefix, local) = split_prefixed_name(key);
+        if let Some(prefix) = namespace_declaration(prefix, local) {
+            let declared = self.scopes.last().and_then(|scope| {
+                scope
+                    .namespaces
+                    .iter()
+                    .find(|(p, _)| p == prefix)
+                    .map(|(_, uri)| uri)
+            });
+
+            // a prefix can only be declared once on an element
+            return match declared {
+                Some(uri) if uri == value => Ok(()),
+                Some(uri) => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "namespace prefix {:?} is already bound to {:?}",
+                        prefix, uri
+                    ),
+                )),
+                None => self.declare_namespace(prefix.to_owned(), value.to_owned()),
+            };
+        }
+
+        let key = match split_expanded_name(key) {
+            None => Cow::Borrowed(key),
+            Some(("", local)) | Some(("*", local)) => Cow::Borrowed(local),
+            Some((uri, local)) => {
+                let prefix = match self.lookup_prefix(uri, false) {
+                    Some(prefix) => prefix,
+                    None => {
+                        let prefix = self.new_prefix(uri);
+                        self.declare_namespace(prefix.clone(), uri.to_owned())?;
+                        prefix
+                    }
+                };
+                Cow::Owned(format!("{}:{}", prefix, local))
+            }
+        };
+
+        self.write_attribute_separator()?;
+        write!(self.inner, r#"{}="{}""#, key, xml_escape(value))
     }
 
     pub fn write_text(&mut self, content: &str) -> Result<()> {
+        self.mark_mixed_content();
         write!(self.inner, "{}", xml_escape(content))
     }
 
+    /// Writes `content` as is, without escaping.
+    pub fn write_raw(&mut self, content: &str) -> Result<()> {
+        self.write_indent()?;
+        write!(self.inner, "{}", content)
+    }
+
+    /// Writes an XML declaration.
+    ///
+    /// ```rust
+    /// use strong_xml::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new());
+    ///
+    /// writer.write_declaration("1.0", Some("UTF-8"), Some(true)).unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(writer.into_inner()).unwrap(),
+    ///     r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#
+    /// );
+    /// ```
+    pub fn write_declaration(
+        &mut self,
+        version: &str,
+        encoding: Option<&str>,
+        standalone: Option<bool>,
+    ) -> Result<()> {
+        self.write_indent()?;
+        write!(self.inner, r#"<?xml version="{}""#, xml_escape(version))?;
+        if let Some(encoding) = encoding {
+            write!(self.inner, r#" encoding="{}""#, xml_escape(encoding))?;
+        }
+        if let Some(standalone) = standalone {
+            let standalone = if standalone { "yes" } else { "no" };
+            write!(self.inner, r#" standalone="{}""#, standalone)?;
+        }
+        write!(self.inner, "?>")
+    }
+
+    /// Writes `content` as a comment.
+    ///
+    /// Returns an error of kind `InvalidInput` if `content` contains `--`
+    /// or ends with `-`, which can't be written in a comment.
+    ///
+    /// ```rust
+    /// use strong_xml::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new());
+    ///
+    /// writer.write_comment(" note ").unwrap();
+    /// assert!(writer.write_comment("a -- b").is_err());
+    ///
+    /// assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), "<!-- note -->");
+    /// ```
+    pub fn write_comment(&mut self, content: &str) -> Result<()> {
+        if content.contains("--") || content.ends_with('-') {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid XML comment: {:?}", content),
+            ));
+        }
+
+        self.write_indent()?;
+        write!(self.inner, "<!--{}-->", content)
+    }
+
+    /// Writes a processing instruction with `target` and `content`.
+    ///
+    /// Returns an error of kind `InvalidInput` if `target` isn't a valid name
+    /// or is `xml`, or `content` contains `?>`.
+    ///
+    /// ```rust
+    /// use strong_xml::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new());
+    ///
+    /// writer.write_processing_instruction("mso-application", r#"progid="Word.Document""#).unwrap();
+    /// writer.write_processing_instruction("page-break", "").unwrap();
+    /// assert!(writer.write_processing_instruction("xml", "").is_err());
+    /// assert!(writer.write_processing_instruction("pi", "?>").is_err());
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(writer.into_inner()).unwrap(),
+    ///     r#"<?mso-application progid="Word.Document"?><?page-break?>"#
+    /// );
+    /// ```
+    pub fn write_processing_instruction(&mut self, target: &str, content: &str) -> Result<()> {
+        if target.is_empty()
+            || target.eq_ignore_ascii_case("xml")
+            || target.contains(|c: char| c.is_whitespace() || "<>?&\"'=/".contains(c))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid XML processing instruction target: {:?}", target),
+            ));
+        }
+
+        if content.contains("?>") {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid XML processing instruction: {:?}", content),
+            ));
+        }
+
+        self.write_indent()?;
+
+        if content.is_empty() {
+            write!(self.inner, "<?{}?>", target)
+        } else {
+            write!(self.inner, "<?{} {}?>", target, content)
+        }
+    }
+
     pub fn write_cdata_text(&mut self, content: &str) -> Result<()> {
+        self.mark_mixed_content();
         write!(self.inner, "<![CDATA[{}]]>", content)
     }
 
@@ -36,7 +419,26 @@ impl<W: Write> XmlWriter<W> {
         write!(self.inner, ">")
     }
 
+    /// Writes an element containing only text, like `<tag>content</tag>`.
+    ///
+    /// Returns an `InvalidInput` error if `tag` isn't a valid XML name,
+    /// since it can come from the keys of a `flatten_text` map.
+    ///
+    /// ```rust
+    /// use strong_xml::XmlWriter;
+    ///
+    /// let mut writer = XmlWriter::new(Vec::new());
+    ///
+    /// assert!(writer.write_flatten_text("a b", "text", false).is_err());
+    /// ```
     pub fn write_flatten_text(&mut self, tag: &str, content: &str, is_cdata: bool) -> Result<()> {
+        if !is_xml_name(tag) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid XML element name: {:?}", tag),
+            ));
+        }
+
         self.write_element_start(tag)?;
         self.write_element_end_open()?;
         if is_cdata {
@@ -49,10 +451,153 @@ impl<W: Write> XmlWriter<W> {
     }
 
     pub fn write_element_end_close(&mut self, tag: &str) -> Result<()> {
-        write!(self.inner, "</{}>", tag)
+        let scope = self.scopes.pop();
+
+        // the end tag of element-only content goes on its own line
+        if let (Some(indent), Some(scope)) = (&self.indent, &scope) {
+            if scope.has_children && !scope.is_mixed {
+                write!(
+                    self.inner,
+                    "{}{}",
+                    self.newline,
+                    indent.repeat(self.scopes.len())
+                )?;
+            }
+        }
+
+        match (scope, split_expanded_name(tag)) {
+            (
+                Some(Scope {
+                    tag: Some(qname), ..
+                }),
+                Some(_),
+            ) => write!(self.inner, "</{}>", qname),
+            (_, Some((_, local))) => write!(self.inner, "</{}>", local),
+            _ => write!(self.inner, "</{}>", tag),
+        }
     }
 
     pub fn write_element_end_empty(&mut self) -> Result<()> {
+        self.scopes.pop();
         write!(self.inner, "/>")
     }
+
+    /// Writes the line break and indentation before an element, comment
+    /// or processing instruction in pretty printing.
+    fn write_indent(&mut self) -> Result<()> {
+        let indent = match &self.indent {
+            Some(indent) => indent,
+            None => return Ok(()),
+        };
+
+        match self.scopes.last_mut() {
+            Some(scope) if scope.is_mixed => return Ok(()),
+            Some(scope) => scope.has_children = true,
+            // nothing goes before the first node of a document
+            None if !self.is_started => {
+                self.is_started = true;
+                return Ok(());
+            }
+            None => (),
+        }
+
+        write!(
+            self.inner,
+            "{}{}",
+            self.newline,
+            indent.repeat(self.scopes.len())
+        )
+    }
+
+    /// Writes the whitespace before an attribute.
+    fn write_attribute_separator(&mut self) -> Result<()> {
+        match &self.indent {
+            Some(indent) if self.attribute_per_line => write!(
+                self.inner,
+                "{}{}",
+                self.newline,
+                indent.repeat(self.scopes.len())
+            ),
+            _ => write!(self.inner, " "),
+        }
+    }
+
+    fn declare_namespace(&mut self, prefix: String, uri: String) -> Result<()> {
+        self.write_attribute_separator()?;
+
+        if prefix.is_empty() {
+            write!(self.inner, r#"xmlns="{}""#, xml_escape(&uri))?;
+        } else {
+            write!(self.inner, r#"xmlns:{}="{}""#, prefix, xml_escape(&uri))?;
+        }
+
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.namespaces.push((prefix, uri));
+        }
+
+        Ok(())
+    }
+
+    // finds the namespace bound to `prefix`, empty prefix stands for the default namespace
+    fn lookup_namespace(&self, prefix: &str) -> Option<&str> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.namespaces.iter().rev())
+            .find(|(p, _)| p == prefix)
+            .map(|(_, uri)| uri.as_str())
+            .filter(|uri| !uri.is_empty())
+    }
+
+    // finds a prefix which is currently bound to `uri`
+    fn lookup_prefix(&self, uri: &str, allow_default: bool) -> Option<String> {
+        if uri == XML_NAMESPACE {
+            return Some("xml".to_owned());
+        }
+
+        if allow_default && self.lookup_namespace("") == Some(uri) {
+            return Some(String::new());
+        }
+
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.namespaces.iter().rev())
+            .filter(|(p, u)| u == uri && !p.is_empty())
+            // make sure it isn't shadowed by an inner declaration
+            .find(|(p, _)| self.lookup_namespace(p) == Some(uri))
+            .map(|(p, _)| p.clone())
+    }
+
+    fn preferred_prefix(&self, uri: &str) -> Option<&str> {
+        self.prefixes
+            .iter()
+            .find(|(_, u)| u == uri)
+            .map(|(p, _)| p.as_str())
+    }
+
+    // the default namespace is never generated, since attributes can't use it,
+    // and the prefix shouldn't be already declared on the current element
+    fn new_prefix(&mut self, uri: &str) -> String {
+        let is_declared = |scopes: &[Scope], prefix: &str| {
+            scopes
+                .last()
+                .is_some_and(|scope| scope.namespaces.iter().any(|(p, _)| p == prefix))
+        };
+
+        match self.preferred_prefix(uri) {
+            Some(prefix) if !prefix.is_empty() && !is_declared(&self.scopes, prefix) => {
+                return prefix.to_owned();
+            }
+            _ => (),
+        }
+
+        loop {
+            let prefix = format!("ns{}", self.generated_prefixes);
+            self.generated_prefixes += 1;
+            if !is_declared(&self.scopes, &prefix) {
+                return prefix;
+            }
+        }
+    }
 }
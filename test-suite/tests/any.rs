@@ -0,0 +1,106 @@
+use std::borrow::Cow;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "order")]
+struct Order<'a> {
+    #[xml(attr = "id")]
+    id: Cow<'a, str>,
+    #[xml(flatten_text = "amount")]
+    amount: usize,
+    #[xml(child = "item")]
+    items: Vec<Item<'a>>,
+    #[xml(any)]
+    extensions: Vec<Cow<'a, str>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "item")]
+struct Item<'a> {
+    #[xml(text)]
+    name: Cow<'a, str>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "note")]
+struct Note {
+    #[xml(any)]
+    content: Vec<String>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(ns = "urn:a", tag = "root")]
+struct Root<'a> {
+    #[xml(any)]
+    extensions: Vec<Cow<'a, str>>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let mut order = Order::from_str(
+        r#"<order id="1"><v:tracking xmlns:v="urn:vendor" code="x"><v:step/>a &amp; b</v:tracking><amount>1</amount><item>apple</item><v:gift xmlns:v="urn:vendor"/></order>"#,
+    )?;
+
+    assert_eq!(
+        order,
+        Order {
+            id: "1".into(),
+            amount: 1,
+            items: vec![Item {
+                name: "apple".into()
+            }],
+            extensions: vec![
+                r#"<v:tracking xmlns:v="urn:vendor" code="x"><v:step/>a &amp; b</v:tracking>"#
+                    .into(),
+                r#"<v:gift xmlns:v="urn:vendor"/>"#.into(),
+            ],
+        }
+    );
+
+    order.amount = 2;
+
+    assert_eq!(
+        order.to_string()?,
+        r#"<order id="1"><amount>2</amount><item>apple</item><v:tracking xmlns:v="urn:vendor" code="x"><v:step/>a &amp; b</v:tracking><v:gift xmlns:v="urn:vendor"/></order>"#
+    );
+
+    assert_eq!(
+        Note::from_str(r#"<note><a>1</a><b/></note>"#)?,
+        Note {
+            content: vec!["<a>1</a>".into(), "<b/>".into()]
+        }
+    );
+
+    assert_eq!((Note { content: vec![] }).to_string()?, r#"<note/>"#);
+
+    // namespaces declared by ancestors are declared again
+    let root = Root::from_str(
+        r#"<a:root xmlns:a="urn:a" xmlns:v="urn:v"><v:ext/><a:other v:x="1"><v:y xmlns:v="urn:w"/></a:other><ext xmlns:v="urn:v"/></a:root>"#,
+    )?;
+
+    assert_eq!(
+        root.extensions,
+        vec![
+            r#"<v:ext xmlns:v="urn:v"/>"#,
+            r#"<a:other xmlns:a="urn:a" xmlns:v="urn:v" v:x="1"><v:y xmlns:v="urn:w"/></a:other>"#,
+            r#"<ext xmlns:v="urn:v"/>"#,
+        ]
+    );
+
+    assert_eq!(
+        root.to_string()?,
+        r#"<ns0:root xmlns:ns0="urn:a"><v:ext xmlns:v="urn:v"/><a:other xmlns:a="urn:a" xmlns:v="urn:v" v:x="1"><v:y xmlns:v="urn:w"/></a:other><ext xmlns:v="urn:v"/></ns0:root>"#
+    );
+
+    assert_eq!(
+        Root::from_str(r#"<root xmlns="urn:a"><ext/></root>"#)?.extensions,
+        vec![r#"<ext xmlns="urn:a"/>"#]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "div")]
+struct Div<'a> {
+    #[xml(attr = "id")]
+    id: Cow<'a, str>,
+    #[xml(any_attr)]
+    attrs: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    #[xml(any)]
+    children: Vec<Cow<'a, str>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "span")]
+struct Span {
+    #[xml(any_attr)]
+    attrs: BTreeMap<String, String>,
+}
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(tag = "span")]
+struct HashSpan<'a> {
+    #[xml(attr = "class")]
+    class: Option<Cow<'a, str>>,
+    #[xml(any_attr)]
+    attrs: HashMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let mut div =
+        Div::from_str(r#"<div data-x="1" id="a" xmlns:v="urn:vendor" v:flag="on"><v:ext/></div>"#)?;
+
+    assert_eq!(
+        div,
+        Div {
+            id: "a".into(),
+            attrs: vec![
+                ("data-x".into(), "1".into()),
+                ("{urn:vendor}flag".into(), "on".into()),
+            ],
+            children: vec![r#"<v:ext xmlns:v="urn:vendor"/>"#.into()],
+        }
+    );
+
+    div.id = "b".into();
+
+    assert_eq!(
+        div.to_string()?,
+        r#"<div id="b" data-x="1" xmlns:ns0="urn:vendor" ns0:flag="on"><v:ext xmlns:v="urn:vendor"/></div>"#
+    );
+
+    // namespace declarations aren't captured, so the round trip is stable
+    assert_eq!(Div::from_str(&div.to_string()?)?, div);
+    assert_eq!(
+        Div::from_str(&div.to_string()?)?.to_string()?,
+        div.to_string()?
+    );
+
+    // values are unescaped and escaped again
+    let div = Div::from_str(r#"<div id="a" data-x="a &amp; b"/>"#)?;
+
+    assert_eq!(div.attrs, vec![("data-x".into(), "a & b".into())]);
+    assert_eq!(div.to_string()?, r#"<div id="a" data-x="a &amp; b"/>"#);
+
+    let span = Span::from_str(r#"<span b="2" a="1"/>"#)?;
+
+    assert_eq!(
+        span.attrs,
+        vec![
+            ("a".to_owned(), "1".to_owned()),
+            ("b".to_owned(), "2".to_owned())
+        ]
+        .into_iter()
+        .collect()
+    );
+
+    assert_eq!(span.to_string()?, r#"<span a="1" b="2"/>"#);
+
+    let span = HashSpan::from_str(r#"<span class="c" style="s"/>"#)?;
+
+    assert_eq!(span.class, Some("c".into()));
+    assert_eq!(span.attrs.len(), 1);
+    assert_eq!(span.attrs["style"], "s");
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use strong_xml::{XmlRead, XmlResult, XmlWrite};
+use strong_xml::{XmlError, XmlRead, XmlResult, XmlWrite};
 
 #[derive(XmlRead, XmlWrite, PartialEq, Debug)]
 #[xml(tag = "attr")]
@@ -21,6 +21,21 @@ struct Text {
     text: bool,
 }
 
+#[derive(XmlRead, XmlWrite, PartialEq, Debug)]
+#[xml(tag = "formats")]
+struct Formats {
+    #[xml(attr = "numeric", bool = "numeric")]
+    numeric: bool,
+    #[xml(attr = "yes_no", bool = "yes_no")]
+    yes_no: Option<bool>,
+    #[xml(attr = "custom", bool = "Y|N")]
+    custom: bool,
+    #[xml(attr = "strict", strict_bool)]
+    strict: bool,
+    #[xml(flatten_text = "xsd", bool = "strict_xsd")]
+    xsd: Vec<bool>,
+}
+
 #[test]
 fn test() -> XmlResult<()> {
     let _ = env_logger::builder()
@@ -72,5 +87,45 @@ fn test() -> XmlResult<()> {
         r#"<text><foo>false</foo><foo>true</foo></text>"#,
     );
 
+    assert_eq!(
+        Formats::from_str(
+            r#"<formats numeric="yes" yes_no="0" custom="N" strict="true"><xsd>1</xsd><xsd>false</xsd></formats>"#
+        )?,
+        Formats {
+            numeric: true,
+            yes_no: Some(false),
+            custom: false,
+            strict: true,
+            xsd: vec![true, false],
+        }
+    );
+
+    assert_eq!(
+        (Formats {
+            numeric: true,
+            yes_no: Some(false),
+            custom: true,
+            strict: false,
+            xsd: vec![true, false],
+        })
+        .to_string()?,
+        r#"<formats numeric="1" yes_no="no" custom="Y" strict="false"><xsd>true</xsd><xsd>false</xsd></formats>"#
+    );
+
+    match Formats::from_str(r#"<formats numeric="1" custom="Y" strict="1"/>"#) {
+        Err(XmlError::UnknownValue { name, value }) => {
+            assert_eq!(name, "bool");
+            assert_eq!(value, "1");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert!(matches!(
+        Formats::from_str(
+            r#"<formats numeric="1" custom="Y" strict="true"><xsd>yes</xsd></formats>"#
+        ),
+        Err(XmlError::UnknownValue { .. })
+    ));
+
     Ok(())
 }
@@ -0,0 +1,99 @@
+use strong_xml::{XmlError, XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "log")]
+struct Log<'a> {
+    #[xml(attr = "level")]
+    level: &'a str,
+    #[xml(attr = "source")]
+    source: Option<&'a str>,
+    #[xml(attr = "tags")]
+    tags: Vec<&'a str>,
+    #[xml(flatten_text = "message")]
+    message: &'a str,
+    #[xml(flatten_text = "host", default_value = "localhost")]
+    host: &'a str,
+    #[xml(flatten_text = "detail")]
+    details: Vec<&'a str>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "line")]
+struct Line<'a> {
+    #[xml(text)]
+    text: &'a str,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let xml = r#"<log level="info" tags="a b"><message>started</message><host>db</host><detail>x</detail><detail><![CDATA[<y>]]></detail></log>"#;
+
+    let log = Log::from_str(xml)?;
+
+    assert_eq!(
+        log,
+        Log {
+            level: "info",
+            source: None,
+            tags: vec!["a", "b"],
+            message: "started",
+            host: "db",
+            details: vec!["x", "<y>"],
+        }
+    );
+
+    assert_eq!(
+        log.to_string()?,
+        r#"<log level="info" tags="a b"><message>started</message><host>db</host><detail>x</detail><detail>&lt;y&gt;</detail></log>"#
+    );
+
+    assert_eq!(
+        Log::from_str(r#"<log level="warn"><message>m</message></log>"#)?.host,
+        "localhost"
+    );
+
+    match Log::from_str(r#"<log level="p &lt; q"><message>m</message></log>"#) {
+        Err(XmlError::CannotBorrow { name, field, value }) => {
+            assert_eq!(name, "Log");
+            assert_eq!(field, "level");
+            assert_eq!(value, "p &lt; q");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert!(matches!(
+        Log::from_str(r#"<log level="info" tags="a &amp;b"><message>m</message></log>"#),
+        Err(XmlError::CannotBorrow { .. })
+    ));
+
+    assert_eq!(Line::from_str("<line>text</line>")?.text, "text");
+
+    match Line::from_str("<line>a &amp; b</line>") {
+        Err(XmlError::CannotBorrow { name, field, value }) => {
+            assert_eq!(name, "Line");
+            assert_eq!(field, "text");
+            assert_eq!(value, "a &amp; b");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    match Line::from_str("<line>a<!-- b -->c</line>") {
+        Err(XmlError::CannotBorrow { value, .. }) => assert_eq!(value, "a<!-- b -->c"),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    match Log::from_str(r#"<log level="info"><message><![CDATA[a]]>b</message></log>"#) {
+        Err(XmlError::CannotBorrow { field, value, .. }) => {
+            assert_eq!(field, "message");
+            assert_eq!(value, "<![CDATA[a]]>b");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+#[allow(clippy::vec_box)]
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "outline")]
+struct Outline<'a> {
+    #[xml(attr = "text")]
+    text: Cow<'a, str>,
+    #[xml(child = "outline")]
+    children: Vec<Box<Outline<'a>>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "g")]
+struct Group {
+    #[xml(child = "g")]
+    group: Option<Box<Group>>,
+    #[xml(child = "style")]
+    style: Rc<Style>,
+    #[xml(child = "title")]
+    title: Option<Arc<Title>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "style")]
+struct Style {
+    #[xml(attr = "fill")]
+    fill: String,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "title")]
+struct Title {
+    #[xml(text)]
+    text: String,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "path")]
+struct Path {
+    #[xml(child = "point")]
+    points: Rc<Points>,
+    #[xml(child = "mark")]
+    marks: Option<Arc<Marks>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "point")]
+struct Points(Vec<Point>);
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "point")]
+struct Point {
+    #[xml(attr = "x")]
+    x: usize,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "mark")]
+struct Marks(Vec<Mark>);
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "mark")]
+struct Mark {
+    #[xml(attr = "at")]
+    at: usize,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let xml = r#"<outline text="a"><outline text="b"><outline text="c"/></outline><outline text="d"/></outline>"#;
+
+    let outline = Outline {
+        text: "a".into(),
+        children: vec![
+            Box::new(Outline {
+                text: "b".into(),
+                children: vec![Box::new(Outline {
+                    text: "c".into(),
+                    children: vec![],
+                })],
+            }),
+            Box::new(Outline {
+                text: "d".into(),
+                children: vec![],
+            }),
+        ],
+    };
+
+    assert_eq!(Outline::from_str(xml)?, outline);
+    assert_eq!(outline.to_string()?, xml);
+
+    let xml = r#"<g><g><style fill="red"/></g><style fill="blue"/><title>shapes</title></g>"#;
+
+    let group = Group {
+        group: Some(Box::new(Group {
+            group: None,
+            style: Rc::new(Style { fill: "red".into() }),
+            title: None,
+        })),
+        style: Rc::new(Style {
+            fill: "blue".into(),
+        }),
+        title: Some(Arc::new(Title {
+            text: "shapes".into(),
+        })),
+    };
+
+    assert_eq!(Group::from_str(xml)?, group);
+    assert_eq!(group.to_string()?, xml);
+
+    // runs of siblings are collected into the same newtype behind Rc and Arc
+    let xml = r#"<path><point x="1"/><mark at="1"/><point x="2"/><mark at="2"/></path>"#;
+
+    assert_eq!(
+        Path::from_str(xml)?,
+        Path {
+            points: Rc::new(Points(vec![Point { x: 1 }, Point { x: 2 }])),
+            marks: Some(Arc::new(Marks(vec![Mark { at: 1 }, Mark { at: 2 }]))),
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use smallvec::SmallVec;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use strong_xml::{XmlError, XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "geometry")]
+struct Geometry<'a> {
+    #[xml(attr = "size")]
+    size: [u32; 2],
+    #[xml(attr = "tags")]
+    tags: BTreeSet<Cow<'a, str>>,
+    #[xml(child = "coord")]
+    coords: [Coord; 3],
+    #[xml(flatten_text = "label")]
+    labels: VecDeque<Cow<'a, str>>,
+    #[xml(flatten_text = "layer")]
+    layers: HashSet<u32>,
+    #[xml(flatten_text = "flag")]
+    flags: SmallVec<[bool; 2]>,
+    #[xml(child = "note")]
+    notes: Option<Vec<Note<'a>>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "coord")]
+struct Coord {
+    #[xml(text)]
+    values: [f64; 2],
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "note")]
+struct Note<'a> {
+    #[xml(text)]
+    text: Cow<'a, str>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "area")]
+struct Area {
+    #[xml(flatten)]
+    origin: Origin,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+struct Origin {
+    #[xml(attr = "origin")]
+    origin: [i32; 2],
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let xml = r#"<geometry size="4 5" tags="a b"><coord>0 1</coord><coord>2 3</coord><coord>4 5</coord><label>x</label><label>y</label><layer>1</layer><flag>true</flag><note>n</note></geometry>"#;
+
+    let geometry = Geometry::from_str(xml)?;
+
+    assert_eq!(
+        geometry,
+        Geometry {
+            size: [4, 5],
+            tags: vec!["a".into(), "b".into()].into_iter().collect(),
+            coords: [
+                Coord { values: [0.0, 1.0] },
+                Coord { values: [2.0, 3.0] },
+                Coord { values: [4.0, 5.0] },
+            ],
+            labels: vec!["x".into(), "y".into()].into_iter().collect(),
+            layers: vec![1].into_iter().collect(),
+            flags: SmallVec::from_slice(&[true]),
+            notes: Some(vec![Note { text: "n".into() }]),
+        }
+    );
+
+    assert_eq!(geometry.to_string()?, xml);
+
+    let geometry = Geometry::from_str(
+        r#"<geometry size="4 5"><coord>0 1</coord><coord>2 3</coord><coord>4 5</coord></geometry>"#,
+    )?;
+
+    assert!(geometry.tags.is_empty());
+    assert!(geometry.labels.is_empty());
+    assert_eq!(geometry.notes, None);
+
+    match Geometry::from_str(
+        r#"<geometry size="4 5"><coord>0 1</coord><coord>2 3</coord></geometry>"#,
+    ) {
+        Err(XmlError::LengthMismatch {
+            name,
+            field,
+            expected,
+            found,
+        }) => {
+            assert_eq!(name, "Geometry");
+            assert_eq!(field, "coords");
+            assert_eq!((expected, found), (3, 2));
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert!(matches!(
+        Coord::from_str(r#"<coord>0 1 2</coord>"#),
+        Err(XmlError::LengthMismatch { .. })
+    ));
+
+    match Geometry::from_str(
+        r#"<geometry size="4 5"><coord>0 1</coord><coord>2 3</coord><coord>4 5</coord><layer>1</layer><layer>1</layer></geometry>"#,
+    ) {
+        Err(XmlError::DuplicateValue { name, field }) => {
+            assert_eq!(name, "Geometry");
+            assert_eq!(field, "layers");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    let area = Area::from_str(r#"<area origin="-1 1"/>"#)?;
+
+    assert_eq!(area.origin.origin, [-1, 1]);
+    assert_eq!(area.to_string()?, r#"<area origin="-1 1"/>"#);
+
+    assert!(matches!(
+        Area::from_str(r#"<area/>"#),
+        Err(XmlError::LengthMismatch { .. })
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use std::borrow::Cow;
+use strong_xml::{XmlError, XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "config")]
+struct Config<'a> {
+    #[xml(comment)]
+    comments: Vec<Cow<'a, str>>,
+    #[xml(child = "server")]
+    servers: Vec<Server<'a>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "server")]
+struct Server<'a> {
+    #[xml(attr = "host")]
+    host: Cow<'a, str>,
+    #[xml(flatten_text = "port")]
+    port: u16,
+    #[xml(comment)]
+    note: Option<String>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let config = Config::from_str(
+        r#"<config>
+            <!-- production -->
+            <server host="a"><port><!-- default -->80</port><!-- primary --><!-- second --></server>
+            <!-- backup -->
+            <server host="b"><port>8080</port></server>
+        </config>"#,
+    )?;
+
+    assert_eq!(
+        config,
+        Config {
+            comments: vec![" production ".into(), " backup ".into()],
+            servers: vec![
+                Server {
+                    host: "a".into(),
+                    port: 80,
+                    note: Some(" primary ".into()),
+                },
+                Server {
+                    host: "b".into(),
+                    port: 8080,
+                    note: None,
+                },
+            ],
+        }
+    );
+
+    assert_eq!(
+        config.to_string()?,
+        r#"<config><!-- production --><!-- backup --><server host="a"><port>80</port><!-- primary --></server><server host="b"><port>8080</port></server></config>"#
+    );
+
+    assert_eq!(
+        (Config {
+            comments: vec![],
+            servers: vec![],
+        })
+        .to_string()?,
+        r#"<config/>"#
+    );
+
+    assert_eq!(
+        (Config {
+            comments: vec!["only".into()],
+            servers: vec![],
+        })
+        .to_string()?,
+        r#"<config><!--only--></config>"#
+    );
+
+    assert!(matches!(
+        (Config {
+            comments: vec!["a -- b".into()],
+            servers: vec![],
+        })
+        .to_string(),
+        Err(XmlError::IO(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::borrow::Cow;
+use std::time::Duration;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+mod seconds {
+    use std::borrow::Cow;
+    use std::num::ParseIntError;
+    use std::time::Duration;
+
+    pub fn from_xml(s: &str) -> Result<Duration, ParseIntError> {
+        s.parse().map(Duration::from_secs)
+    }
+
+    pub fn to_xml(duration: &Duration) -> Cow<'static, str> {
+        duration.as_secs().to_string().into()
+    }
+}
+
+fn default_host() -> Cow<'static, str> {
+    "localhost".into()
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "server")]
+struct Server<'a> {
+    #[xml(attr = "host", default = "default_host")]
+    host: Cow<'a, str>,
+    #[xml(attr = "port", default_value = "8080")]
+    port: u16,
+    #[xml(attr = "name", default_value = "main")]
+    name: Cow<'a, str>,
+    #[xml(attr = "secure", default_value = "yes")]
+    secure: bool,
+    #[xml(flatten_text = "timeout", with = "seconds", default_value = "30")]
+    timeout: Duration,
+    #[xml(child = "limit", default_value = r#"<limit max="10"/>"#)]
+    limit: Limit,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "limit")]
+struct Limit {
+    #[xml(attr = "max")]
+    max: usize,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "broken")]
+struct Broken {
+    #[xml(attr = "port", default_value = "port")]
+    port: u16,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    assert_eq!(
+        Server::from_str(r#"<server/>"#)?,
+        Server {
+            host: "localhost".into(),
+            port: 8080,
+            name: "main".into(),
+            secure: true,
+            timeout: Duration::from_secs(30),
+            limit: Limit { max: 10 },
+        }
+    );
+
+    assert_eq!(
+        Server::from_str(
+            r#"<server host="example.com" port="80" name="backup" secure="no"><timeout>5</timeout><limit max="1"/></server>"#
+        )?,
+        Server {
+            host: "example.com".into(),
+            port: 80,
+            name: "backup".into(),
+            secure: false,
+            timeout: Duration::from_secs(5),
+            limit: Limit { max: 1 },
+        }
+    );
+
+    assert!(Broken::from_str(r#"<broken/>"#).is_err());
+
+    assert_eq!(
+        Broken::from_str(r#"<broken port="80"/>"#)?,
+        Broken { port: 80 }
+    );
+
+    Ok(())
+}
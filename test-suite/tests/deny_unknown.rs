@@ -0,0 +1,113 @@
+use std::borrow::Cow;
+use strong_xml::{XmlError, XmlRead, XmlReader, XmlResult};
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(tag = "config", deny_unknown)]
+struct Config<'a> {
+    #[xml(attr = "name")]
+    name: Option<Cow<'a, str>>,
+    #[xml(child = "server")]
+    server: Server,
+}
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(tag = "server")]
+struct Server {
+    #[xml(default, flatten_text = "port")]
+    port: u16,
+}
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(deny_unknown)]
+enum Shape {
+    #[xml(tag = "circle")]
+    Circle {
+        #[xml(attr = "r")]
+        r: usize,
+    },
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    assert_eq!(
+        Config::from_str(r#"<config name="a"><server><port>80</port></server></config>"#)?,
+        Config {
+            name: Some("a".into()),
+            server: Server { port: 80 }
+        }
+    );
+
+    // namespace declarations aren't unknown attributes
+    assert_eq!(
+        Config::from_str(r#"<config xmlns="urn:c" xmlns:x="urn:x" name="a"><server/></config>"#)?,
+        Config {
+            name: Some("a".into()),
+            server: Server { port: 0 }
+        }
+    );
+
+    match Config::from_str(r#"<config nmae="a"><server/></config>"#) {
+        Err(XmlError::UnknownAttribute {
+            element,
+            attribute,
+            line,
+            column,
+        }) => {
+            assert_eq!(element, "config");
+            assert_eq!(attribute, "nmae");
+            assert_eq!((line, column), (1, 9));
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    match Config::from_str("<config>\n  <server/>\n  <sever/>\n</config>") {
+        Err(XmlError::UnknownElement {
+            element,
+            child,
+            line,
+            column,
+        }) => {
+            assert_eq!(element, "config");
+            assert_eq!(child, "sever");
+            assert_eq!((line, column), (3, 4));
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    // `server` doesn't deny unknown children by itself
+    assert_eq!(
+        Config::from_str(r#"<config><server><prot>80</prot></server></config>"#)?,
+        Config {
+            name: None,
+            server: Server { port: 0 }
+        }
+    );
+
+    let mut reader = XmlReader::new(r#"<server><prot>80</prot></server>"#);
+    reader.set_deny_unknown(true);
+
+    match Server::from_reader(&mut reader) {
+        Err(XmlError::UnknownElement { element, child, .. }) => {
+            assert_eq!(element, "server");
+            assert_eq!(child, "prot");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    let mut reader = XmlReader::new(r#"<server xmlns:x="urn:x"><port>80</port></server>"#);
+    reader.set_deny_unknown(true);
+
+    assert_eq!(Server::from_reader(&mut reader)?, Server { port: 80 });
+
+    assert!(matches!(
+        Shape::from_str(r#"<circle r="1" x="0"/>"#),
+        Err(XmlError::UnknownAttribute { .. })
+    ));
+
+    Ok(())
+}
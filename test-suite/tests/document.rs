@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+use strong_xml::{XmlDeclaration, XmlDocument, XmlMisc, XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "server")]
+struct Server<'a> {
+    #[xml(attr = "host")]
+    host: Cow<'a, str>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<?xml-stylesheet type="text/xsl" href="style.xsl"?>
+<!DOCTYPE server SYSTEM "server.dtd" [
+  <!ENTITY host "localhost">
+]>
+<!-- generated -->
+<server host="a"/>
+<!-- end -->
+"#;
+
+    let document = XmlDocument::<Server>::from_str(xml)?;
+
+    assert_eq!(
+        document,
+        XmlDocument {
+            declaration: Some(XmlDeclaration {
+                version: "1.0".into(),
+                encoding: Some("UTF-8".into()),
+                standalone: Some(false),
+            }),
+            before_doctype: vec![XmlMisc::ProcessingInstruction {
+                target: "xml-stylesheet".into(),
+                content: r#"type="text/xsl" href="style.xsl""#.into(),
+            }],
+            doctype: Some(
+                "<!DOCTYPE server SYSTEM \"server.dtd\" [\n  <!ENTITY host \"localhost\">\n]>"
+                    .into()
+            ),
+            prolog: vec![XmlMisc::Comment(" generated ".into())],
+            root: Server { host: "a".into() },
+            epilog: vec![XmlMisc::Comment(" end ".into())],
+        }
+    );
+
+    // whitespace between the items isn't kept, but their order is
+    assert_eq!(
+        document.to_string()?,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\
+         <?xml-stylesheet type=\"text/xsl\" href=\"style.xsl\"?>\
+         <!DOCTYPE server SYSTEM \"server.dtd\" [\n  <!ENTITY host \"localhost\">\n]>\
+         <!-- generated --><server host=\"a\"/><!-- end -->"
+    );
+    assert_eq!(XmlDocument::from_str(&document.to_string()?)?, document);
+
+    let xml = r#"<!-- a --><!DOCTYPE server><?b?><server host="a"/>"#;
+    let document = XmlDocument::<Server>::from_str(xml)?;
+
+    assert_eq!(
+        document.before_doctype,
+        vec![XmlMisc::Comment(" a ".into())]
+    );
+    assert_eq!(document.to_string()?, xml);
+
+    let document = XmlDocument::<Server>::from_str(r#"<server host="b"/>"#)?;
+
+    assert_eq!(document.declaration, None);
+    assert_eq!(document.doctype, None);
+    assert_eq!(document.to_string()?, r#"<server host="b"/>"#);
+
+    let document = XmlDocument::<Server>::from_str(r#"<!-- a --><server host="b"/>"#)?;
+
+    assert!(document.before_doctype.is_empty());
+    assert_eq!(document.prolog, vec![XmlMisc::Comment(" a ".into())]);
+
+    assert_eq!(
+        XmlDocument::new(Server { host: "c".into() }).to_string()?,
+        r#"<?xml version="1.0" encoding="UTF-8"?><server host="c"/>"#
+    );
+
+    Ok(())
+}
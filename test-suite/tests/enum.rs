@@ -32,6 +32,7 @@ enum CD {
     D(D),
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(XmlWrite, XmlRead, PartialEq, Debug)]
 enum ABCDEFG {
     #[xml(tag = "a", tag = "b")]
@@ -0,0 +1,52 @@
+use std::borrow::Cow;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "page")]
+struct Page<'a> {
+    #[xml(flatten_text = "title")]
+    title: Cow<'a, str>,
+    #[xml(child = "item")]
+    items: Vec<Item<'a>>,
+    #[xml(flatten_text = "footer")]
+    footer: Option<Cow<'a, str>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "item")]
+struct Item<'a> {
+    #[xml(text)]
+    text: Cow<'a, str>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let xml = r#"<page><title>Title</title><item>a</item><item>b</item><footer>Footer</footer><item>c</item></page>"#;
+
+    let page = Page::from_str(xml)?;
+
+    assert_eq!(
+        page,
+        Page {
+            title: "Title".into(),
+            items: vec![
+                Item { text: "a".into() },
+                Item { text: "b".into() },
+                Item { text: "c".into() },
+            ],
+            footer: Some("Footer".into()),
+        }
+    );
+
+    assert_eq!(
+        page.to_string()?,
+        r#"<page><title>Title</title><item>a</item><item>b</item><item>c</item><footer>Footer</footer></page>"#
+    );
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::borrow::Cow;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+struct CommonAttrs<'a> {
+    #[xml(attr = "id")]
+    id: Option<Cow<'a, str>>,
+    #[xml(attr = "lang", default_value = "en")]
+    lang: Cow<'a, str>,
+    #[xml(flatten)]
+    dates: Dates,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+struct Dates {
+    #[xml(attr = "created")]
+    created: Option<u64>,
+    #[xml(attr = "modified")]
+    modified: Option<u64>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+struct Meta<'a> {
+    #[xml(flatten_text = "title")]
+    title: Cow<'a, str>,
+    #[xml(child = "link")]
+    links: Vec<Link<'a>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "link")]
+struct Link<'a> {
+    #[xml(attr = "href")]
+    href: Cow<'a, str>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "article")]
+struct Article<'a> {
+    #[xml(flatten)]
+    common: CommonAttrs<'a>,
+    #[xml(attr = "author")]
+    author: Cow<'a, str>,
+    #[xml(flatten)]
+    meta: Meta<'a>,
+    #[xml(flatten_text = "p")]
+    paragraphs: Vec<Cow<'a, str>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "section")]
+struct Section<'a> {
+    #[xml(flatten)]
+    common: CommonAttrs<'a>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let article = Article {
+        common: CommonAttrs {
+            id: Some("a1".into()),
+            lang: "de".into(),
+            dates: Dates {
+                created: Some(1),
+                modified: None,
+            },
+        },
+        author: "me".into(),
+        meta: Meta {
+            title: "Title".into(),
+            links: vec![Link { href: "/".into() }],
+        },
+        paragraphs: vec!["text".into()],
+    };
+
+    assert_eq!(
+        article.to_string()?,
+        r#"<article id="a1" lang="de" created="1" author="me"><title>Title</title><link href="/"/><p>text</p></article>"#
+    );
+
+    assert_eq!(
+        Article::from_str(
+            r#"<article author="me" created="1" lang="de" id="a1"><title>Title</title><p>text</p><link href="/"/></article>"#
+        )?,
+        article
+    );
+
+    assert_eq!(
+        Section::from_str(r#"<section modified="2"/>"#)?,
+        Section {
+            common: CommonAttrs {
+                id: None,
+                lang: "en".into(),
+                dates: Dates {
+                    created: None,
+                    modified: Some(2),
+                },
+            },
+        }
+    );
+
+    assert_eq!(
+        (Section {
+            common: CommonAttrs {
+                id: None,
+                lang: "en".into(),
+                dates: Dates {
+                    created: None,
+                    modified: None,
+                },
+            },
+        })
+        .to_string()?,
+        r#"<section lang="en"/>"#
+    );
+
+    // missing required children of the flattened struct
+    assert!(Article::from_str(r#"<article author="me"></article>"#).is_err());
+
+    Ok(())
+}
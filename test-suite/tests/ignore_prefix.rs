@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+use strong_xml::{XmlRead, XmlReader, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "Envelope", ignore_prefix)]
+struct Envelope<'a> {
+    #[xml(attr = "version")]
+    version: Option<Cow<'a, str>>,
+    #[xml(child = "Body")]
+    body: Body<'a>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "Body", ignore_prefix)]
+struct Body<'a> {
+    #[xml(flatten_text = "Message")]
+    message: Cow<'a, str>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "Body")]
+struct PlainBody<'a> {
+    #[xml(flatten_text = "Message")]
+    message: Cow<'a, str>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    assert_eq!(
+        Envelope::from_str(
+            r#"<soap:Envelope xmlns:soap="urn:soap" soap:version="1.2"><soap:Body><soap:Message>hello</soap:Message></soap:Body></soap:Envelope>"#
+        )?,
+        Envelope {
+            version: Some("1.2".into()),
+            body: Body {
+                message: "hello".into()
+            }
+        }
+    );
+
+    assert_eq!(
+        Envelope::from_str(
+            r#"<env:Envelope xmlns:env="urn:other"><Body><x:Message xmlns:x="urn:x">hello</x:Message></Body></env:Envelope>"#
+        )?,
+        Envelope {
+            version: None,
+            body: Body {
+                message: "hello".into()
+            }
+        }
+    );
+
+    assert_eq!(
+        (Envelope {
+            version: Some("1.2".into()),
+            body: Body {
+                message: "hello".into()
+            }
+        })
+        .to_string()?,
+        r#"<Envelope version="1.2"><Body><Message>hello</Message></Body></Envelope>"#
+    );
+
+    // unprefixed tags match by local name anyway
+    assert_eq!(
+        PlainBody::from_str(r#"<s:Body><s:Message>hello</s:Message></s:Body>"#)?,
+        PlainBody {
+            message: "hello".into()
+        }
+    );
+
+    let mut reader = XmlReader::new(r#"<s:Body><s:Message>hello</s:Message></s:Body>"#);
+    reader.set_ignore_prefix(true);
+
+    assert_eq!(
+        PlainBody::from_reader(&mut reader)?,
+        PlainBody {
+            message: "hello".into()
+        }
+    );
+
+    Ok(())
+}
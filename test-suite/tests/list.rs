@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+use strong_xml::{XmlError, XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "polygon")]
+struct Polygon<'a> {
+    #[xml(attr = "class")]
+    class: Vec<Cow<'a, str>>,
+    #[xml(attr = "points", separator = ",")]
+    points: Vec<i32>,
+    #[xml(attr = "flags")]
+    flags: Vec<bool>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "ids")]
+struct Ids {
+    #[xml(text)]
+    ids: Vec<usize>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "words")]
+struct Words<'a> {
+    #[xml(text, separator = ";")]
+    words: Vec<Cow<'a, str>>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let polygon = Polygon {
+        class: vec!["a".into(), "b".into(), "c".into()],
+        points: vec![1, 2, -3, 4],
+        flags: vec![true, false],
+    };
+
+    assert_eq!(
+        Polygon::from_str(r#"<polygon class=" a  b c" points="1, 2,-3 ,4" flags="1 off"/>"#)?,
+        polygon
+    );
+
+    assert_eq!(
+        polygon.to_string()?,
+        r#"<polygon class="a b c" points="1,2,-3,4" flags="true false"/>"#
+    );
+
+    assert_eq!(
+        Polygon::from_str(r#"<polygon points=""/>"#)?,
+        Polygon {
+            class: vec![],
+            points: vec![],
+            flags: vec![],
+        }
+    );
+
+    assert_eq!(
+        (Polygon {
+            class: vec![],
+            points: vec![],
+            flags: vec![],
+        })
+        .to_string()?,
+        r#"<polygon/>"#
+    );
+
+    assert!(Polygon::from_str(r#"<polygon points="1,a"/>"#).is_err());
+
+    assert_eq!(
+        Ids::from_str("<ids>\n  4 5\n  6\n</ids>")?,
+        Ids { ids: vec![4, 5, 6] }
+    );
+
+    assert_eq!(
+        (Ids { ids: vec![4, 5, 6] }).to_string()?,
+        "<ids>4 5 6</ids>"
+    );
+
+    assert_eq!(Ids::from_str("<ids></ids>")?, Ids { ids: vec![] });
+
+    assert_eq!(
+        Words::from_str("<words>a &amp; b; c</words>")?,
+        Words {
+            words: vec!["a & b".into(), "c".into()]
+        }
+    );
+
+    assert_eq!(
+        (Words {
+            words: vec!["a & b".into(), "c".into()]
+        })
+        .to_string()?,
+        "<words>a &amp; b;c</words>"
+    );
+
+    // items which would be split when read back can't be written
+    match (Polygon {
+        class: vec!["a b".into()],
+        points: vec![],
+        flags: vec![],
+    })
+    .to_string()
+    {
+        Err(XmlError::InvalidListItem { name, field, item }) => {
+            assert_eq!(name, "Polygon");
+            assert_eq!(field, "class");
+            assert_eq!(item, "a b");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert!(matches!(
+        (Words {
+            words: vec!["a;b".into()]
+        })
+        .to_string(),
+        Err(XmlError::InvalidListItem { .. })
+    ));
+
+    Ok(())
+}
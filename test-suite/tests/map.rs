@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use strong_xml::{XmlError, XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "config")]
+struct Config<'a> {
+    #[xml(child = "entry", key = "key")]
+    entries: BTreeMap<String, Entry<'a>>,
+    #[xml(child = "properties")]
+    properties: Properties<'a>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "entry")]
+struct Entry<'a> {
+    #[xml(attr = "key")]
+    key: Cow<'a, str>,
+    #[xml(text)]
+    value: Cow<'a, str>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "properties")]
+struct Properties<'a> {
+    #[xml(attr = "version")]
+    version: Option<usize>,
+    #[xml(flatten_text = "version")]
+    inner_version: Option<usize>,
+    #[xml(flatten_text)]
+    values: BTreeMap<Cow<'a, str>, usize>,
+}
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(tag = "servers")]
+struct Servers {
+    #[xml(child = "server", key = "port")]
+    servers: HashMap<u16, Server>,
+}
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(tag = "server")]
+struct Server {
+    #[xml(attr = "host")]
+    host: String,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let xml = r#"<config><entry key="a">1</entry><entry key="b">2</entry><properties version="1"><bar>2</bar><foo>1</foo><version>2</version></properties></config>"#;
+
+    let config = Config::from_str(xml)?;
+
+    assert_eq!(
+        config,
+        Config {
+            entries: vec![
+                (
+                    "a".into(),
+                    Entry {
+                        key: "a".into(),
+                        value: "1".into()
+                    }
+                ),
+                (
+                    "b".into(),
+                    Entry {
+                        key: "b".into(),
+                        value: "2".into()
+                    }
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            properties: Properties {
+                version: Some(1),
+                inner_version: Some(2),
+                values: vec![("bar".into(), 2), ("foo".into(), 1)]
+                    .into_iter()
+                    .collect(),
+            },
+        }
+    );
+
+    assert_eq!(
+        config.to_string()?,
+        r#"<config><entry key="a">1</entry><entry key="b">2</entry><properties version="1"><version>2</version><bar>2</bar><foo>1</foo></properties></config>"#
+    );
+
+    assert_eq!(
+        Properties::from_str(r#"<properties/>"#)?,
+        Properties {
+            version: None,
+            inner_version: None,
+            values: BTreeMap::new(),
+        }
+    );
+
+    let servers = Servers::from_str(
+        r#"<servers><server port="80" host="a"/><server host="b" port="443"/></servers>"#,
+    )?;
+
+    assert_eq!(servers.servers.len(), 2);
+    assert_eq!(servers.servers[&80].host, "a");
+    assert_eq!(servers.servers[&443].host, "b");
+
+    match Servers::from_str("<servers>\n  <server port=\"80\" host=\"a\"/>\n  <server port=\"80\" host=\"b\"/>\n</servers>") {
+        Err(XmlError::DuplicateKey {
+            element,
+            child,
+            key,
+            line,
+            column,
+        }) => {
+            assert_eq!(element, "servers");
+            assert_eq!(child, "server");
+            assert_eq!(key, "80");
+            assert_eq!((line, column), (3, 4));
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert!(matches!(
+        Servers::from_str(r#"<servers><server host="a"/></servers>"#),
+        Err(XmlError::MissingField { .. })
+    ));
+
+    assert!(matches!(
+        Properties::from_str(r#"<properties><foo>1</foo><foo>2</foo></properties>"#),
+        Err(XmlError::DuplicateKey { .. })
+    ));
+
+    // keys of `flatten_text` maps are written as tags
+    let properties = Properties {
+        version: None,
+        inner_version: None,
+        values: vec![("a b".into(), 1)].into_iter().collect(),
+    };
+
+    assert!(matches!(properties.to_string(), Err(XmlError::IO(_))));
+
+    // the key attribute of the child is written, not the map key
+    let config = Config {
+        entries: vec![(
+            "a".into(),
+            Entry {
+                key: "b".into(),
+                value: "1".into(),
+            },
+        )]
+        .into_iter()
+        .collect(),
+        properties: Properties::from_str("<properties/>")?,
+    };
+
+    assert_eq!(
+        Config::from_str(&config.to_string()?)?.entries["b"].value,
+        "1"
+    );
+
+    Ok(())
+}
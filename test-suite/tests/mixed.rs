@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "p")]
+struct Paragraph<'a> {
+    #[xml(attr = "id")]
+    id: Option<Cow<'a, str>>,
+    #[xml(mixed, child = "b", child = "i")]
+    content: Vec<Inline<'a>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+enum Inline<'a> {
+    #[xml(text)]
+    Text(Cow<'a, str>),
+    #[xml(tag = "b")]
+    Bold(Bold<'a>),
+    #[xml(tag = "i")]
+    Italic {
+        #[xml(mixed, child = "b")]
+        content: Vec<Inline<'a>>,
+    },
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "b")]
+struct Bold<'a> {
+    #[xml(text)]
+    text: Cow<'a, str>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+enum Number {
+    #[xml(text)]
+    Value(usize),
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "numbers")]
+struct Numbers {
+    #[xml(mixed, child = "unknown")]
+    content: Vec<Number>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let xml = r#"<p id="1">Hello <b>world</b>, <i>again &amp; <b>again</b></i>!</p>"#;
+
+    let paragraph = Paragraph {
+        id: Some("1".into()),
+        content: vec![
+            Inline::Text("Hello ".into()),
+            Inline::Bold(Bold {
+                text: "world".into(),
+            }),
+            Inline::Text(", ".into()),
+            Inline::Italic {
+                content: vec![
+                    Inline::Text("again & ".into()),
+                    Inline::Bold(Bold {
+                        text: "again".into(),
+                    }),
+                ],
+            },
+            Inline::Text("!".into()),
+        ],
+    };
+
+    assert_eq!(Paragraph::from_str(xml)?, paragraph);
+
+    assert_eq!(paragraph.to_string()?, xml);
+
+    assert_eq!(
+        Paragraph::from_str(r#"<p><!-- comment --><u>skipped</u><b>bold</b></p>"#)?,
+        Paragraph {
+            id: None,
+            content: vec![Inline::Bold(Bold {
+                text: "bold".into()
+            })],
+        }
+    );
+
+    assert_eq!(
+        Paragraph::from_str(r#"<p/>"#)?,
+        Paragraph {
+            id: None,
+            content: vec![],
+        }
+    );
+
+    assert_eq!(
+        Numbers::from_str(r#"<numbers>1<skip/>2</numbers>"#)?,
+        Numbers {
+            content: vec![Number::Value(1), Number::Value(2)],
+        }
+    );
+
+    assert!(Numbers::from_str(r#"<numbers>one</numbers>"#).is_err());
+
+    Ok(())
+}
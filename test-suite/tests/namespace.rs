@@ -0,0 +1,165 @@
+use std::borrow::Cow;
+use strong_xml::{XmlRead, XmlResult, XmlWrite, XmlWriter};
+
+const SOAP: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(ns = "http://schemas.xmlsoap.org/soap/envelope/", tag = "Envelope")]
+struct Envelope<'a> {
+    #[xml(child = "Body")]
+    body: Body<'a>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(ns = "http://schemas.xmlsoap.org/soap/envelope/", tag = "Body")]
+struct Body<'a> {
+    #[xml(ns = "urn:example", child = "add", child = "sub")]
+    op: Option<Op<'a>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(ns = "urn:example")]
+enum Op<'a> {
+    #[xml(tag = "add")]
+    Add(Add<'a>),
+    #[xml(tag = "sub")]
+    Sub(Sub),
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(ns = "urn:example", tag = "add")]
+struct Add<'a> {
+    #[xml(attr = "id")]
+    id: Cow<'a, str>,
+    #[xml(ns = "urn:example", attr = "unit")]
+    unit: Option<Cow<'a, str>>,
+    #[xml(flatten_text = "arg")]
+    args: Vec<usize>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(ns = "urn:example", tag = "sub")]
+struct Sub {}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(ns = "urn:a", tag = "root")]
+struct NsRoot {
+    #[xml(child = "plain")]
+    plain: Vec<Plain>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "plain")]
+struct Plain {}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let expected = Envelope {
+        body: Body {
+            op: Some(Op::Add(Add {
+                id: "1".into(),
+                unit: Some("m".into()),
+                args: vec![1, 2],
+            })),
+        },
+    };
+
+    assert_eq!(
+        Envelope::from_str(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body><ex:add xmlns:ex="urn:example" id="1" ex:unit="m"><ex:arg>1</ex:arg><ex:arg>2</ex:arg></ex:add></soap:Body></soap:Envelope>"#
+        )?,
+        expected
+    );
+
+    assert_eq!(
+        Envelope::from_str(
+            r#"<env:Envelope xmlns:env="http://schemas.xmlsoap.org/soap/envelope/"><env:Body><add xmlns="urn:example" xmlns:e="urn:example" e:unit="m" id="1"><arg>1</arg><e:arg>2</e:arg></add></env:Body></env:Envelope>"#
+        )?,
+        expected
+    );
+
+    // elements and attributes in other namespaces are skipped
+    assert_eq!(
+        Envelope::from_str(
+            r#"<Envelope xmlns="http://schemas.xmlsoap.org/soap/envelope/" xmlns:o="urn:other"><Body><o:sub/><sub xmlns="urn:example" xmlns:o="urn:other" o:unit="m"/></Body></Envelope>"#
+        )?,
+        Envelope {
+            body: Body {
+                op: Some(Op::Sub(Sub {})),
+            },
+        }
+    );
+
+    // unqualified elements don't match
+    assert_eq!(
+        Body::from_str(&format!(r#"<Body xmlns="{}"><sub xmlns=""/></Body>"#, SOAP))?,
+        Body { op: None }
+    );
+
+    assert!(Envelope::from_str(r#"<Envelope><Body/></Envelope>"#).is_err());
+
+    assert_eq!(
+        expected.to_string()?,
+        r#"<ns0:Envelope xmlns:ns0="http://schemas.xmlsoap.org/soap/envelope/"><ns0:Body><ns1:add xmlns:ns1="urn:example" id="1" ns1:unit="m"><ns1:arg>1</ns1:arg><ns1:arg>2</ns1:arg></ns1:add></ns0:Body></ns0:Envelope>"#
+    );
+
+    assert_eq!(Envelope::from_str(&expected.to_string()?)?, expected);
+
+    let mut writer = XmlWriter::new(Vec::new());
+    writer.register_prefix("soap", SOAP);
+    writer.register_prefix("ex", "urn:example");
+    expected.to_writer(&mut writer)?;
+
+    assert_eq!(
+        String::from_utf8(writer.into_inner()).unwrap(),
+        r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body><ex:add xmlns:ex="urn:example" id="1" ex:unit="m"><ex:arg>1</ex:arg><ex:arg>2</ex:arg></ex:add></soap:Body></soap:Envelope>"#
+    );
+
+    // tags without namespace match by local name, whichever the prefix
+    assert_eq!(
+        NsRoot::from_str(r#"<a:root xmlns:a="urn:a"><plain/><a:plain/></a:root>"#)?,
+        NsRoot {
+            plain: vec![Plain {}, Plain {}]
+        }
+    );
+
+    assert_eq!(
+        NsRoot::from_str(r#"<root xmlns="urn:a"><plain/></root>"#)?,
+        NsRoot {
+            plain: vec![Plain {}]
+        }
+    );
+
+    assert_eq!(
+        NsRoot::from_str(r#"<a:root xmlns:a="urn:a"><a:plain/></a:root>"#)?,
+        NsRoot {
+            plain: vec![Plain {}]
+        }
+    );
+
+    // children without namespace keep having no namespace when written
+    let root = NsRoot {
+        plain: vec![Plain {}],
+    };
+
+    assert_eq!(
+        root.to_string()?,
+        r#"<ns0:root xmlns:ns0="urn:a"><plain/></ns0:root>"#
+    );
+    assert_eq!(NsRoot::from_str(&root.to_string()?)?, root);
+
+    let mut writer = XmlWriter::new(Vec::new());
+    writer.register_prefix("", "urn:a");
+    root.to_writer(&mut writer)?;
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(xml, r#"<root xmlns="urn:a"><plain xmlns=""/></root>"#);
+    assert_eq!(NsRoot::from_str(&xml)?, root);
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, TimeZone, Utc};
+use std::borrow::Cow;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "list")]
+struct List<'a> {
+    #[xml(child = "id")]
+    id: Id,
+    #[xml(child = "name")]
+    name: Name<'a>,
+    #[xml(child = "item")]
+    items: Items,
+    #[xml(child = "visible")]
+    visible: Option<Visible>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "id")]
+struct Id(String);
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "name")]
+struct Name<'a>(Cow<'a, str>);
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "visible")]
+struct Visible(bool);
+
+// path-qualified text types are known by their last segment
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "code")]
+struct Code(std::string::String);
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "label")]
+struct Label<'a>(std::borrow::Cow<'a, std::primitive::str>);
+
+// other `FromStr` types aren't known to be text
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "stamp")]
+struct Stamp(#[xml(text)] DateTime<Utc>);
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "item")]
+struct Items(Vec<Item>);
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "item")]
+struct Item {
+    #[xml(attr = "value")]
+    value: usize,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "item")]
+struct Footer(Option<Item>);
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+enum Value<'a> {
+    #[xml(tag = "int")]
+    Int(i64),
+    #[xml(tag = "str")]
+    Str(Cow<'a, str>),
+    #[xml(tag = "ints")]
+    Ints(Vec<u8>),
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    assert_eq!(Id::from_str("<id>a</id>")?, Id("a".into()));
+    assert_eq!(Id("a".into()).to_string()?, "<id>a</id>");
+
+    assert_eq!(Visible::from_str("<visible>yes</visible>")?, Visible(true));
+    assert_eq!(Visible(false).to_string()?, "<visible>false</visible>");
+
+    assert_eq!(Code::from_str("<code>a</code>")?, Code("a".into()));
+    assert_eq!(Code("a".into()).to_string()?, "<code>a</code>");
+    assert_eq!(Label::from_str("<label>a</label>")?, Label("a".into()));
+
+    let stamp = Stamp(Utc.with_ymd_and_hms(2020, 1, 2, 3, 4, 5).unwrap());
+    assert_eq!(
+        Stamp::from_str("<stamp>2020-01-02T03:04:05Z</stamp>")?,
+        stamp
+    );
+    assert_eq!(stamp.to_string()?, "<stamp>2020-01-02 03:04:05 UTC</stamp>");
+
+    let xml = r#"<list><id>1</id><name>a &amp; b</name><item value="1"/><item value="2"/></list>"#;
+
+    let list = List::from_str(xml)?;
+
+    assert_eq!(
+        list,
+        List {
+            id: Id("1".into()),
+            name: Name("a & b".into()),
+            items: Items(vec![Item { value: 1 }, Item { value: 2 }]),
+            visible: None,
+        }
+    );
+    assert_eq!(list.to_string()?, xml);
+
+    // whitespace between siblings is skipped
+    assert_eq!(
+        List::from_str(
+            "<list>\n  <id>1</id>\n  <name>a</name>\n  <item value=\"1\"/>\n  <item value=\"2\"/>\n  <visible>1</visible>\n</list>"
+        )?
+        .items
+        .0
+        .len(),
+        2
+    );
+
+    // runs of siblings split by other elements are appended
+    assert_eq!(
+        List::from_str(
+            r#"<list><item value="1"/><id>1</id><item value="2"/><name>a</name><item value="3"/></list>"#
+        )?
+        .items,
+        Items(vec![Item { value: 1 }, Item { value: 2 }, Item { value: 3 }])
+    );
+
+    assert_eq!(
+        Items(vec![Item { value: 1 }, Item { value: 2 }]).to_string()?,
+        r#"<item value="1"/><item value="2"/>"#
+    );
+
+    assert_eq!(Footer::from_str("")?, Footer(None));
+    assert_eq!(
+        Footer::from_str(r#"<item value="3"/>"#)?,
+        Footer(Some(Item { value: 3 }))
+    );
+
+    assert_eq!(Value::from_str("<int>-1</int>")?, Value::Int(-1));
+    assert_eq!(Value::from_str("<str>s</str>")?, Value::Str("s".into()));
+    assert_eq!(
+        Value::from_str("<ints>1 2</ints>")?,
+        Value::Ints(vec![1, 2])
+    );
+    assert_eq!(Value::Ints(vec![1, 2]).to_string()?, "<ints>1 2</ints>");
+
+    Ok(())
+}
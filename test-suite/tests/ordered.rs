@@ -0,0 +1,106 @@
+use std::borrow::Cow;
+use strong_xml::{XmlError, XmlRead, XmlResult};
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(tag = "invoice", ordered)]
+struct Invoice<'a> {
+    #[xml(attr = "id")]
+    id: Option<Cow<'a, str>>,
+    #[xml(flatten_text = "date")]
+    date: Cow<'a, str>,
+    #[xml(flatten_text = "note")]
+    note: Option<Cow<'a, str>>,
+    #[xml(child = "line")]
+    lines: Vec<Line>,
+    #[xml(flatten_text = "total")]
+    total: usize,
+}
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(tag = "line")]
+struct Line {
+    #[xml(attr = "amount")]
+    amount: usize,
+}
+
+#[derive(XmlRead, PartialEq, Debug)]
+#[xml(ordered)]
+enum Document<'a> {
+    #[xml(tag = "invoice")]
+    Invoice {
+        #[xml(flatten_text = "date")]
+        date: Cow<'a, str>,
+        #[xml(flatten_text = "total")]
+        total: usize,
+    },
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    assert_eq!(
+        Invoice::from_str(
+            r#"<invoice id="1"><date>2020-01-01</date><line amount="1"/><line amount="2"/><unknown/><total>3</total></invoice>"#
+        )?,
+        Invoice {
+            id: Some("1".into()),
+            date: "2020-01-01".into(),
+            note: None,
+            lines: vec![Line { amount: 1 }, Line { amount: 2 }],
+            total: 3,
+        }
+    );
+
+    match Invoice::from_str(
+        "<invoice>\n  <date>2020-01-01</date>\n  <total>3</total>\n  <line amount=\"1\"/>\n</invoice>",
+    ) {
+        Err(XmlError::UnorderedElement {
+            element,
+            child,
+            line,
+            column,
+        }) => {
+            assert_eq!(element, "invoice");
+            assert_eq!(child, "line");
+            assert_eq!((line, column), (4, 4));
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    // singular children can only appear once
+    match Invoice::from_str(
+        r#"<invoice><date>2020-01-01</date><date>2020-01-02</date><total>3</total></invoice>"#,
+    ) {
+        Err(XmlError::DuplicateElement { element, child, .. }) => {
+            assert_eq!(element, "invoice");
+            assert_eq!(child, "date");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert!(matches!(
+        Invoice::from_str(
+            r#"<invoice><date>2020-01-01</date><note>a</note><note>b</note><total>3</total></invoice>"#
+        ),
+        Err(XmlError::DuplicateElement { .. })
+    ));
+
+    assert!(matches!(
+        Document::from_str(r#"<invoice><total>3</total><date>2020-01-01</date></invoice>"#),
+        Err(XmlError::UnorderedElement { .. })
+    ));
+
+    assert_eq!(
+        Document::from_str(r#"<invoice><date>2020-01-01</date><total>3</total></invoice>"#)?,
+        Document::Invoice {
+            date: "2020-01-01".into(),
+            total: 3,
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+use strong_xml::{XmlError, XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "document")]
+struct Document<'a> {
+    #[xml(pi = "mso-application")]
+    application: Option<Cow<'a, str>>,
+    #[xml(comment)]
+    comments: Vec<Cow<'a, str>>,
+    #[xml(child = "body")]
+    body: Body<'a>,
+    #[xml(pi = "page-break")]
+    page_breaks: Vec<Cow<'a, str>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "body")]
+struct Body<'a> {
+    #[xml(text)]
+    text: Cow<'a, str>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let document = Document::from_str(
+        r#"<document><?mso-application progid="Word.Document"?><!-- c --><?unknown?><body>t<?ignored?></body><?page-break?><?page-break  before ?></document>"#,
+    )?;
+
+    assert_eq!(
+        document,
+        Document {
+            application: Some(r#"progid="Word.Document""#.into()),
+            comments: vec![" c ".into()],
+            body: Body { text: "t".into() },
+            page_breaks: vec!["".into(), "before ".into()],
+        }
+    );
+
+    assert_eq!(
+        document.to_string()?,
+        r#"<document><?mso-application progid="Word.Document"?><!-- c --><body>t</body><?page-break?><?page-break before ?></document>"#
+    );
+
+    assert!(matches!(
+        (Document {
+            application: Some("?>".into()),
+            comments: vec![],
+            body: Body { text: "".into() },
+            page_breaks: vec![],
+        })
+        .to_string(),
+        Err(XmlError::IO(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use std::borrow::Cow;
+use strong_xml::{XmlDocument, XmlRead, XmlResult, XmlWrite, XmlWriter};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "config")]
+struct Config<'a> {
+    #[xml(attr = "name")]
+    name: Cow<'a, str>,
+    #[xml(comment)]
+    comments: Vec<Cow<'a, str>>,
+    #[xml(child = "server")]
+    servers: Vec<Server<'a>>,
+    #[xml(child = "p")]
+    description: Option<Paragraph<'a>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "server")]
+struct Server<'a> {
+    #[xml(attr = "host")]
+    host: Cow<'a, str>,
+    #[xml(attr = "port")]
+    port: u16,
+    #[xml(flatten_text = "alias")]
+    aliases: Vec<Cow<'a, str>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "p")]
+struct Paragraph<'a> {
+    #[xml(mixed, child = "b")]
+    content: Vec<Inline<'a>>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+enum Inline<'a> {
+    #[xml(text)]
+    Text(Cow<'a, str>),
+    #[xml(tag = "b")]
+    Bold(Bold<'a>),
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "b")]
+struct Bold<'a> {
+    #[xml(text)]
+    text: Cow<'a, str>,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let config = Config {
+        name: "main".into(),
+        comments: vec![" servers ".into()],
+        servers: vec![
+            Server {
+                host: "a".into(),
+                port: 80,
+                aliases: vec!["b".into(), "c".into()],
+            },
+            Server {
+                host: "d".into(),
+                port: 443,
+                aliases: vec![],
+            },
+        ],
+        description: Some(Paragraph {
+            content: vec![
+                Inline::Bold(Bold {
+                    text: "Main".into(),
+                }),
+                Inline::Text(" servers".into()),
+            ],
+        }),
+    };
+
+    let xml = r#"<config name="main">
+  <!-- servers -->
+  <server host="a" port="80">
+    <alias>b</alias>
+    <alias>c</alias>
+  </server>
+  <server host="d" port="443"/>
+  <p><b>Main</b> servers</p>
+</config>"#;
+
+    assert_eq!(config.to_string_pretty()?, xml);
+
+    assert_eq!(Config::from_str(xml)?, config);
+
+    let mut writer = XmlWriter::new(Vec::new());
+    writer.set_indent("\t");
+    writer.set_newline("\r\n");
+    writer.set_attribute_per_line(true);
+
+    config.servers[0].to_writer(&mut writer)?;
+
+    assert_eq!(
+        String::from_utf8(writer.inner)?,
+        "<server\r\n\thost=\"a\"\r\n\tport=\"80\">\r\n\t<alias>b</alias>\r\n\t<alias>c</alias>\r\n</server>"
+    );
+
+    assert_eq!(
+        XmlDocument::new(Paragraph { content: vec![] }).to_string_pretty()?,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<p/>"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "list")]
+struct List<'a, T> {
+    #[xml(child = "item")]
+    items: Vec<Item<'a>>,
+    #[xml(skip_read, attr = "count")]
+    count: usize,
+    #[xml(skip_write, attr = "version")]
+    version: Option<Cow<'a, str>>,
+    #[xml(skip)]
+    cache: Vec<usize>,
+    #[xml(skip)]
+    marker: PhantomData<T>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "item")]
+struct Item<'a> {
+    #[xml(text)]
+    text: Cow<'a, str>,
+    #[xml(skip)]
+    len: usize,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    assert_eq!(
+        List::<()>::from_str(r#"<list count="5" version="1"><item>a</item></list>"#)?,
+        List {
+            items: vec![Item {
+                text: "a".into(),
+                len: 0
+            }],
+            count: 0,
+            version: Some("1".into()),
+            cache: vec![],
+            marker: PhantomData,
+        }
+    );
+
+    assert_eq!(
+        (List::<()> {
+            items: vec![Item {
+                text: "a".into(),
+                len: 1
+            }],
+            count: 1,
+            version: Some("1".into()),
+            cache: vec![1, 2, 3],
+            marker: PhantomData,
+        })
+        .to_string()?,
+        r#"<list count="1"><item>a</item></list>"#
+    );
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use strong_xml::{XmlError, XmlRead, XmlResult, XmlValue, XmlWrite};
+
+#[derive(XmlValue, PartialEq, Debug)]
+enum Color {
+    #[xml(value = "red")]
+    Red,
+    #[xml(value = "green")]
+    Green,
+    #[xml(value = "light-blue")]
+    LightBlue,
+}
+
+#[derive(XmlValue, PartialEq, Debug)]
+enum Unit {
+    #[xml(value = "px")]
+    Pixel,
+    #[xml(value = "%")]
+    Percent,
+    Other(String),
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "shape")]
+struct Shape {
+    #[xml(attr = "color")]
+    color: Color,
+    #[xml(attr = "fill")]
+    fill: Option<Color>,
+    #[xml(flatten_text = "unit")]
+    units: Vec<Unit>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "palette")]
+struct Palette {
+    #[xml(text)]
+    color: Color,
+}
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    assert_eq!("light-blue".parse::<Color>()?, Color::LightBlue);
+    assert_eq!(Color::Green.to_string(), "green");
+
+    assert_eq!("%".parse::<Unit>()?, Unit::Percent);
+    assert_eq!("em".parse::<Unit>()?, Unit::Other("em".into()));
+    assert_eq!(Unit::Other("em".into()).to_string(), "em");
+
+    let shape = Shape {
+        color: Color::Red,
+        fill: Some(Color::LightBlue),
+        units: vec![Unit::Pixel, Unit::Other("em".into())],
+    };
+
+    let xml = r#"<shape color="red" fill="light-blue"><unit>px</unit><unit>em</unit></shape>"#;
+
+    assert_eq!(Shape::from_str(xml)?, shape);
+    assert_eq!(shape.to_string()?, xml);
+
+    assert_eq!(
+        Palette::from_str(r#"<palette>green</palette>"#)?,
+        Palette {
+            color: Color::Green
+        }
+    );
+
+    match Palette::from_str(r#"<palette>Green</palette>"#) {
+        Err(XmlError::FromStr(err)) => {
+            assert_eq!(err.to_string(), r#"unknown value of "Color": "Green""#);
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use strong_xml::{XmlRead, XmlResult, XmlWrite};
+
+mod seconds {
+    use std::borrow::Cow;
+    use std::num::ParseIntError;
+    use std::time::Duration;
+
+    pub fn from_xml(s: &str) -> Result<Duration, ParseIntError> {
+        s.parse().map(Duration::from_secs)
+    }
+
+    pub fn to_xml(duration: &Duration) -> Cow<'static, str> {
+        duration.as_secs().to_string().into()
+    }
+}
+
+mod date {
+    use chrono::{NaiveDate, ParseError};
+    use std::borrow::Cow;
+
+    pub fn from_xml(s: &str) -> Result<NaiveDate, ParseError> {
+        NaiveDate::parse_from_str(s, "%d/%m/%Y")
+    }
+
+    pub fn to_xml(date: &NaiveDate) -> Cow<'static, str> {
+        date.format("%d/%m/%Y").to_string().into()
+    }
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "task")]
+struct Task {
+    #[xml(attr = "timeout", with = "seconds")]
+    timeout: Duration,
+    #[xml(attr = "retry", with = "seconds")]
+    retry: Option<Duration>,
+    #[xml(flatten_text = "due", with = "date")]
+    due: Vec<NaiveDate>,
+}
+
+#[derive(XmlWrite, XmlRead, PartialEq, Debug)]
+#[xml(tag = "elapsed")]
+struct Elapsed(#[xml(text, with = "seconds")] Duration);
+
+#[test]
+fn test() -> XmlResult<()> {
+    let _ = env_logger::builder()
+        .is_test(true)
+        .format_timestamp(None)
+        .try_init();
+
+    let task = Task {
+        timeout: Duration::from_secs(30),
+        retry: None,
+        due: vec![
+            NaiveDate::from_ymd_opt(2020, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2020, 3, 1).unwrap(),
+        ],
+    };
+
+    assert_eq!(
+        task.to_string()?,
+        r#"<task timeout="30"><due>01/02/2020</due><due>01/03/2020</due></task>"#
+    );
+
+    assert_eq!(Task::from_str(&task.to_string()?)?, task);
+
+    assert_eq!(
+        Task::from_str(r#"<task timeout="5" retry="1"/>"#)?,
+        Task {
+            timeout: Duration::from_secs(5),
+            retry: Some(Duration::from_secs(1)),
+            due: vec![],
+        }
+    );
+
+    assert!(Task::from_str(r#"<task timeout="5"><due>2020-01-01</due></task>"#).is_err());
+
+    assert_eq!(
+        Elapsed::from_str(r#"<elapsed>42</elapsed>"#)?,
+        Elapsed(Duration::from_secs(42))
+    );
+
+    assert_eq!(
+        Elapsed(Duration::from_secs(42)).to_string()?,
+        r#"<elapsed>42</elapsed>"#
+    );
+
+    Ok(())
+}